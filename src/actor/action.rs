@@ -1,6 +1,25 @@
-#[derive(Clone, PartialEq, Eq, Hash)]
+use std::fmt;
+
+// Amounts are the player's total bet on the current street
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Action {
+    Fold,
+    Check,
     Call,
-    Raise(usize),
-    Fold
-}
\ No newline at end of file
+    Bet(usize),
+    RaiseTo(usize),
+    AllIn
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Fold => write!(f, "Fold"),
+            Action::Check => write!(f, "Check"),
+            Action::Call => write!(f, "Call"),
+            Action::Bet(amount) => write!(f, "Bet {amount}"),
+            Action::RaiseTo(amount) => write!(f, "Raise to {amount}"),
+            Action::AllIn => write!(f, "All-in")
+        }
+    }
+}
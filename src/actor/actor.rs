@@ -1,11 +1,13 @@
 use std::{collections::HashMap, time::Duration};
 
-use crate::{actor::action::Action, engine::{controls::Controls, timer::Timer}, poker::card::Card};
+use rand::rngs::StdRng;
+
+use crate::{actor::action::Action, engine::{controls::Controls, rules::LegalActions, timer::Timer}, poker::card::Card};
 
 pub struct ActorInfo {
     pub player: usize,
-    pub last_raise: usize, 
-    pub current_bet: usize,
+    pub legal: LegalActions,
+    pub pot: usize,
     pub hand: Vec<Card>,
     pub community: Vec<Card>,
     pub players: HashMap<usize, (usize, usize, bool)>, // Idx -> (chips, bet, folded)
@@ -14,19 +16,32 @@ pub struct ActorInfo {
 pub trait PokerActor {
     fn start_turn(&mut self);
     fn turn_started(&self) -> bool;
-    fn done(&mut self, forced: bool, controls: &mut Controls, info: ActorInfo) -> bool;
+    // Randomness must come from the table RNG so that seeded games can be replayed
+    fn done(&mut self, forced: bool, controls: &mut Controls, info: ActorInfo, rng: &mut StdRng) -> bool;
     fn get_action(&mut self) -> Action;
     fn end_turn(&mut self);
+
+    // Whether to post the optional straddle, None while still deciding
+    fn straddle(&mut self, _controls: &mut Controls, _rng: &mut StdRng) -> Option<bool> {
+        Some(false)
+    }
 }
 
 pub struct SimpleActor {
     started: bool,
+    selected_action: Option<Action>,
     timer: Timer
 }
 
 impl SimpleActor {
     pub fn new() -> SimpleActor {
-        Self { started: false, timer: Timer::new(Duration::from_millis(500)) }
+        Self { started: false, selected_action: None, timer: Timer::new(Duration::from_millis(500)) }
+    }
+}
+
+impl Default for SimpleActor {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -40,15 +55,18 @@ impl PokerActor for SimpleActor {
         self.started
     }
 
-    fn done(&mut self, _forced: bool, _controls: &mut Controls, _info: ActorInfo) -> bool {
+    fn done(&mut self, _forced: bool, _controls: &mut Controls, info: ActorInfo, _rng: &mut StdRng) -> bool {
+        self.selected_action = Some(info.legal.call_or_check());
+
         self.timer.done()
     }
 
     fn get_action(&mut self) -> Action {
-        return Action::Call;
+        self.selected_action.take().unwrap()
     }
 
     fn end_turn(&mut self) {
         self.started = false;
+        self.selected_action = None;
     }
 }
@@ -1,8 +1,8 @@
 use std::{collections::HashMap, time::Duration};
 
-use rand::{rng, seq::IndexedRandom, Rng};
+use rand::{rngs::StdRng, seq::IndexedRandom, Rng};
 
-use crate::{actor::{action::Action, actor::{ActorInfo, PokerActor}}, engine::{controls::Controls, player::BIG_BLIND, timer::Timer}, poker::ai::monte_carlo_likeliness_to_win};
+use crate::{actor::{action::Action, actor::{ActorInfo, PokerActor}}, engine::{controls::Controls, timer::Timer}, poker::ai::monte_carlo_likeliness_to_win};
 
 pub struct AdHocActor {
     started: bool,
@@ -16,11 +16,19 @@ impl AdHocActor {
     }
 }
 
-fn select_weighted(options: &[Action], weights: &[f32]) -> Action {
-    let mut rng = rng();
+impl Default for AdHocActor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Simulations behind each decision, tests play whole hands with them in debug builds
+const EQUITY_ITERS: usize = if cfg!(test) { 500 } else { 100000 };
+
+fn select_weighted(options: &[Action], weights: &[f32], rng: &mut impl Rng) -> Action {
     let weights = options.iter().zip(weights).collect::<HashMap<_, _>>();
 
-    options.choose_weighted(&mut rng, |i| weights[i]).unwrap().clone()
+    options.choose_weighted(rng, |i| weights[i]).unwrap().clone()
 }
 
 impl PokerActor for AdHocActor {
@@ -33,10 +41,8 @@ impl PokerActor for AdHocActor {
         self.started
     }
 
-    fn done(&mut self, _forced: bool, _controls: &mut Controls, info: ActorInfo) -> bool {
+    fn done(&mut self, _forced: bool, _controls: &mut Controls, info: ActorInfo, rng: &mut StdRng) -> bool {
         if self.selected_action.is_none() {
-            let mut rng = rng();
-
             // Constants
             const P_EPSILON: f32 = 0.02;
             const BASE_BLUFF_FREQ: f32 = 0.05;
@@ -47,11 +53,12 @@ impl PokerActor for AdHocActor {
 
             // Estimate winning probability as is
             let num_players = info.players.len();
-            let equity = monte_carlo_likeliness_to_win(&info.hand, &info.community, num_players, 100000);
+            let equity = monte_carlo_likeliness_to_win(&info.hand, &info.community, num_players, EQUITY_ITERS, rng);
             
             // Calculate call metrics
-            let pot = info.players.iter().map(|p| p.1.1).sum::<usize>();
-            let call_amount = info.current_bet - info.players[&info.player].1;
+            let pot = info.pot;
+            let legal = info.legal;
+            let call_amount = legal.call;
             let break_even = call_amount as f32 / (call_amount + pot) as f32;
             let call_frac = call_amount as f32 / info.players[&info.player].0 as f32;
 
@@ -62,15 +69,7 @@ impl PokerActor for AdHocActor {
                 .sum::<f32>() / num_players as f32;
 
             // Action set
-            let player_money = info.players[&info.player].0;
-            let max_raise = player_money - call_amount.min(player_money);
-            let min_raise = BIG_BLIND.max(info.last_raise).min(max_raise);
-            
-            let raise_small = Action::Raise(min_raise);
-            let raise_double = Action::Raise((min_raise * 2).min(max_raise));
-            let raise_triple = Action::Raise((min_raise * 3).min(max_raise));
-            let raise_pot = Action::Raise(info.current_bet.min(max_raise));
-            let raise_double_pot = Action::Raise((info.current_bet * 2).min(max_raise));
+            let [raise_small, raise_double, raise_triple, raise_pot, raise_double_pot] = legal.raise_presets().map(|to| legal.raise_to(to));
 
             // Bluff modelling (more frequent in small stakes)
             let bluff_freq = BASE_BLUFF_FREQ * (1.0 - (call_amount as f32 / pot.max(1) as f32));
@@ -83,41 +82,46 @@ impl PokerActor for AdHocActor {
                 if advantage > FANTASTIC_ADVANTAGE {
                     self.selected_action = Some(select_weighted(
                         &[raise_triple, raise_pot, raise_double_pot], 
-                        &[1.0, advantage, advantage / 2.0]
+                        &[1.0, advantage, advantage / 2.0],
+                        rng
                     ));
 
                 } else if advantage > GREAT_ADVANTAGE {
                     self.selected_action = Some(select_weighted(
                         &[raise_double, raise_triple, raise_pot], 
-                        &[1.0, advantage, advantage / 2.0]
+                        &[1.0, advantage, advantage / 2.0],
+                        rng
                     ));
 
                 } else if advantage > GOOD_ADVANTAGE {
                     self.selected_action = Some(select_weighted(
                         &[raise_small, raise_double], 
-                        &[1.0, advantage]
+                        &[1.0, advantage],
+                        rng
                     ));
 
                 } else {
-                    self.selected_action = Some(Action::Call);
+                    self.selected_action = Some(legal.call_or_check());
                 }
 
             } else if call_frac < CALL_DEFEND_FRAC || rng.random_bool(mdf.into()) {
                 if rng.random_bool(bluff_freq.into()) {
                     self.selected_action = Some(select_weighted(
                         &[raise_small, raise_double], 
-                        &[1.0, 1.0]
+                        &[1.0, 1.0],
+                        rng
                     ));
                     
                 } else {
-                    self.selected_action = Some(Action::Call);
+                    self.selected_action = Some(legal.call_or_check());
                 }
 
             } else {
                 if rng.random_bool(bluff_freq.into()) {
                     self.selected_action = Some(select_weighted(
                         &[raise_small, raise_double], 
-                        &[1.0, 1.0]
+                        &[1.0, 1.0],
+                        rng
                     ));
                     
                 } else {
@@ -133,6 +137,11 @@ impl PokerActor for AdHocActor {
         self.selected_action.as_ref().cloned().unwrap()
     }
 
+    // Straddles are blind, so there is nothing to base them on
+    fn straddle(&mut self, _controls: &mut Controls, rng: &mut StdRng) -> Option<bool> {
+        Some(rng.random_bool(0.3))
+    }
+
     fn end_turn(&mut self) {
         self.started = false;
         self.selected_action = None;
@@ -1,6 +1,7 @@
 use crossterm::event::KeyCode;
+use rand::rngs::StdRng;
 
-use crate::{actor::{action::Action, actor::{ActorInfo, PokerActor}}, engine::{controls::Controls, player::BIG_BLIND}};
+use crate::{actor::{action::Action, actor::{ActorInfo, PokerActor}}, engine::controls::Controls};
 
 pub struct HumanActor {
     started: bool,
@@ -13,6 +14,12 @@ impl HumanActor {
     }
 }
 
+impl Default for HumanActor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PokerActor for HumanActor {
     fn start_turn(&mut self) {
         self.started = true;
@@ -22,39 +29,37 @@ impl PokerActor for HumanActor {
         self.started
     }
 
-    fn done(&mut self, forced: bool, controls: &mut Controls, info: ActorInfo) -> bool {
+    fn done(&mut self, forced: bool, controls: &mut Controls, info: ActorInfo, _rng: &mut StdRng) -> bool {
         if forced {
             return true;
         }
         
-        let player_money = info.players[&info.player].0;
-        let call_amount = info.current_bet - info.players[&info.player].1;
-        let max_raise = player_money - call_amount.min(player_money);
-        let min_raise = BIG_BLIND.max(info.last_raise).min(max_raise);
+        let legal = info.legal;
+        let [raise, raise_double, raise_triple, raise_pot, raise_double_pot] = legal.raise_presets();
 
         if controls.is_pressed(KeyCode::Char('f')) {
             self.selected_action = Some(Action::Fold);
         
         } else if controls.is_pressed(KeyCode::Char('c')) {
-            self.selected_action = Some(Action::Call);
+            self.selected_action = Some(legal.call_or_check());
         
         } else if controls.is_pressed(KeyCode::Char('r')) {
-            self.selected_action = Some(Action::Raise(min_raise));
+            self.selected_action = Some(legal.raise_to(raise));
         
         } else if controls.is_pressed(KeyCode::Char('b')) {
             if controls.is_pressed(KeyCode::Char('d')) {
-                self.selected_action = Some(Action::Raise(info.current_bet.min(max_raise)));
+                self.selected_action = Some(legal.raise_to(raise_pot));
             
             } else if controls.is_pressed(KeyCode::Char('t')) {
-                self.selected_action = Some(Action::Raise((info.current_bet * 2).min(max_raise)));
+                self.selected_action = Some(legal.raise_to(raise_double_pot));
             }
 
         } else {
             if controls.is_pressed(KeyCode::Char('d')) {
-                self.selected_action = Some(Action::Raise((min_raise * 2).min(max_raise)));
+                self.selected_action = Some(legal.raise_to(raise_double));
             
             } else if controls.is_pressed(KeyCode::Char('t')) {
-                self.selected_action = Some(Action::Raise((min_raise * 3).min(max_raise)));
+                self.selected_action = Some(legal.raise_to(raise_triple));
             }
         }
 
@@ -65,6 +70,18 @@ impl PokerActor for HumanActor {
         self.selected_action.as_ref().cloned().unwrap()
     }
 
+    fn straddle(&mut self, controls: &mut Controls, _rng: &mut StdRng) -> Option<bool> {
+        if controls.is_pressed(KeyCode::Char('s')) {
+            Some(true)
+
+        } else if controls.is_pressed(KeyCode::Char('n')) {
+            Some(false)
+
+        } else {
+            None
+        }
+    }
+
     fn end_turn(&mut self) {
         self.started = false;
         self.selected_action = None;
@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+
+use crate::{engine::layout::Pos, poker::card::Card};
+
+// Something moving over the table, drawn over what was on screen when the animation started
+#[derive(Clone, Debug)]
+pub enum Sprite {
+    Card { card: Card, back: bool, from: Pos, to: Pos }, // Stays where it lands
+    Flip { card: Card, at: Pos }, // Turns face up and stays
+    Chips { amount: usize, from: Pos, to: Pos } // Gone once it lands
+}
+
+#[derive(Clone, Debug)]
+struct Motion {
+    sprite: Sprite,
+    delay: Duration,
+    duration: Duration
+}
+
+// Motions of a few sprites, each one starting some time after the animation did
+#[derive(Clone, Debug)]
+pub struct Animation {
+    motions: Vec<Motion>,
+    started: Option<Instant>,
+    skipped: bool
+}
+
+// Position of a sliding sprite at the given progress, from 0 to 1
+pub fn slide(from: Pos, to: Pos, progress: f64) -> Pos {
+    let lerp = |a: usize, b: usize| (a as f64 + (b as f64 - a as f64) * progress).round() as usize;
+
+    Pos { row: lerp(from.row, to.row), col: lerp(from.col, to.col) }
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Animation { motions: vec!(), started: None, skipped: false }
+    }
+
+    pub fn add(&mut self, sprite: Sprite, delay: Duration, duration: Duration) {
+        self.motions.push(Motion { sprite, delay, duration });
+    }
+
+    // Time at which the last motion is over
+    pub fn end(&self) -> Duration {
+        self.motions.iter().map(|m| m.delay + m.duration).max().unwrap_or_default()
+    }
+
+    pub fn start(&mut self) {
+        self.started = Some(Instant::now());
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn skip(&mut self) {
+        self.skipped = true;
+    }
+
+    pub fn done(&self) -> bool {
+        self.skipped || self.started.is_some_and(|t| t.elapsed() >= self.end())
+    }
+
+    // Sprites that already started with their progress, in the order they were added
+    pub fn frames(&self) -> impl Iterator<Item = (&Sprite, f64)> {
+        let elapsed = self.started.map(|t| t.elapsed()).unwrap_or_default();
+
+        self.motions.iter()
+            .filter(move |m| elapsed >= m.delay)
+            .map(move |m| (&m.sprite, ((elapsed - m.delay).as_secs_f64() / m.duration.as_secs_f64()).min(1.0)))
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
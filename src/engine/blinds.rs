@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::timer::Timer;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct BlindLevel {
+    pub small_blind: usize,
+    pub big_blind: usize,
+    #[serde(default)]
+    pub ante: usize,
+    pub minutes: Option<u64>,
+    pub hands: Option<usize>
+}
+
+pub struct BlindSchedule {
+    pub levels: Vec<BlindLevel>,
+    pub level: usize,
+    pub hands_played: usize,
+    pub timer: Timer
+}
+
+impl BlindLevel {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.small_blind == 0 || self.small_blind > self.big_blind {
+            return Err("The small blind of a level must be positive and not greater than its big blind".into());
+        }
+
+        if self.minutes.is_some() == self.hands.is_some() || self.minutes == Some(0) || self.hands == Some(0) {
+            return Err("Every blind level needs either a positive duration in minutes or a positive number of hands".into());
+        }
+
+        Ok(())
+    }
+
+    pub fn timer(&self) -> Timer {
+        Timer::new(Duration::from_secs(self.minutes.unwrap_or(0) * 60))
+    }
+}
+
+impl BlindSchedule {
+    pub fn new(levels: Vec<BlindLevel>) -> Self {
+        let timer = levels[0].timer();
+
+        BlindSchedule { levels, level: 0, hands_played: 0, timer }
+    }
+
+    pub fn current(&self) -> &BlindLevel {
+        &self.levels[self.level]
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.level + 1 == self.levels.len()
+    }
+
+    pub fn start(&mut self) {
+        self.timer.start();
+    }
+
+    pub fn finished(&self) -> bool {
+        match (self.current().minutes, self.current().hands) {
+            (Some(_), _) => self.timer.done(),
+            (_, Some(hands)) => self.hands_played >= hands,
+            _ => false
+        }
+    }
+
+    // Called between hands, returns true if a new level starts
+    pub fn next_hand(&mut self) -> bool {
+        let changed = !self.is_last() && self.finished();
+
+        if changed {
+            self.level += 1;
+            self.hands_played = 0;
+            self.timer = self.current().timer();
+            self.timer.start();
+        }
+
+        self.hands_played += 1;
+
+        changed
+    }
+
+    // Fraction of the level already played and a short description of what is left
+    pub fn progress(&self) -> (f64, String) {
+        if self.is_last() {
+            return (0.0, "Final level".into());
+        }
+
+        match (self.current().minutes, self.current().hands) {
+            (Some(_), _) => {
+                let total = self.timer.duration().as_secs_f64();
+                let left = self.timer.remaining().as_secs();
+
+                (1.0 - left as f64 / total, format!("{:02}:{:02} left", left / 60, left % 60))
+            },
+
+            (_, Some(hands)) => {
+                let left = hands.saturating_sub(self.hands_played);
+                (self.hands_played.min(hands) as f64 / hands as f64, format!("{left} hands left"))
+            },
+
+            _ => (0.0, String::new())
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(small_blind: usize, minutes: Option<u64>, hands: Option<usize>) -> BlindLevel {
+        BlindLevel { small_blind, big_blind: 2 * small_blind, ante: 0, minutes, hands }
+    }
+
+    #[test]
+    fn hand_levels_go_up_once_their_hands_are_played() {
+        let mut schedule = BlindSchedule::new(vec!(level(5, None, Some(2)), level(10, None, Some(3)), level(20, None, Some(1))));
+        schedule.start();
+
+        assert!(!schedule.next_hand());
+        assert_eq!(schedule.progress().0, 0.5);
+        assert!(!schedule.next_hand());
+
+        // The third hand is the first one of the next level
+        assert!(schedule.next_hand());
+        assert_eq!(schedule.current().small_blind, 10);
+        assert_eq!(schedule.progress(), (1.0 / 3.0, "2 hands left".into()));
+
+        assert!(!schedule.next_hand());
+        assert!(!schedule.next_hand());
+        assert!(schedule.next_hand());
+
+        // The last level lasts for the rest of the game
+        assert!(schedule.is_last());
+        assert_eq!(schedule.progress(), (0.0, "Final level".into()));
+        assert!((0..10).all(|_| !schedule.next_hand()));
+        assert_eq!(schedule.current().small_blind, 20);
+    }
+
+    #[test]
+    fn minute_levels_go_up_once_their_time_is_over() {
+        let mut schedule = BlindSchedule::new(vec!(level(5, Some(10), None), level(10, Some(10), None)));
+        schedule.start();
+
+        assert!(!schedule.next_hand());
+        assert!(["10:00 left", "09:59 left"].contains(&schedule.progress().1.as_str()));
+
+        schedule.timer.resume(Duration::from_secs(9 * 60 + 30));
+
+        let (progress, left) = schedule.progress();
+        assert!((0.94..=0.96).contains(&progress), "{progress}");
+        assert!(["00:30 left", "00:29 left"].contains(&left.as_str()), "{left}");
+        assert!(!schedule.next_hand());
+
+        // Hands are not counted, only the time
+        schedule.timer.resume(Duration::from_secs(10 * 60));
+
+        assert!(schedule.next_hand());
+        assert_eq!(schedule.current().small_blind, 10);
+        assert!(!schedule.timer.done());
+    }
+}
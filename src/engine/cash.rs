@@ -0,0 +1,26 @@
+use crate::engine::config::ActorKind;
+
+// Seats of a cash game are kept full: busted players buy chips again or leave their seat to somebody new
+pub struct CashGame {
+    pub buy_in: usize,
+    pub actors: Vec<ActorKind>,
+    pub next_opponent: usize // Number in the name of the next new opponent
+}
+
+impl CashGame {
+    pub fn new(buy_in: usize, actors: Vec<ActorKind>) -> Self {
+        let next_opponent = actors.len() + 1;
+
+        CashGame { buy_in, actors, next_opponent }
+    }
+
+    // Seats played by the AI get their chips back when they bust
+    pub fn refills(&self, seat: usize) -> bool {
+        self.actors[seat] != ActorKind::Human
+    }
+
+    pub fn new_opponent(&mut self) -> String {
+        self.next_opponent += 1;
+        format!("Player {}", self.next_opponent - 1)
+    }
+}
@@ -0,0 +1,330 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{actor::{actor::{PokerActor, SimpleActor}, adhoc::AdHocActor, human::HumanActor}, engine::{blinds::BlindLevel, history::DEFAULT_HISTORY_PATH, palette::ColorDepth, save::DEFAULT_SAVE_PATH, stats::DEFAULT_STATS_PATH, table::{MAX_SEATS, MIN_SEATS}, theme::DEFAULT_THEME}};
+
+pub const DEFAULT_CONFIG_PATH: &str = "console-ace.toml";
+
+const USAGE: &str = "Usage: poker [OPTIONS]
+
+Options:
+    --config <FILE>     Load the table settings from a TOML file
+    --players <N>       Number of seats at the table (2 to 10)
+    --stack <CHIPS>     Starting stack of every player
+    --blinds <SB/BB>    Small and big blinds, e.g. 2/5
+    --ante <CHIPS>      Ante posted by every player
+    --bb-ante           The big blind posts the ante for the whole table
+    --cash              Play a cash game, the stack is the buy-in
+    --buy-in <AMOUNT>   Buy-in of every player of a sit-and-go, they make up the prize pool
+    --run-it-twice      All-in pots are split across two boards
+    --straddle          The player after the big blind may post a straddle of two big blinds
+    --bomb-pot <HANDS>  Play a bomb pot every so many hands
+    --bomb-ante <CHIPS> Ante posted by every player in a bomb pot
+    --payouts <SHARES>  Percentage of the prize pool paid to each place, e.g. 50/30/20
+    --seed <SEED>       Seed for the random number generator
+    --save <FILE>       File where the game is saved when quitting
+    --resume            Resume the game saved in the save file
+    --history <FILE>    File where the hand histories are written
+    --replay <FILE>     Replay the hands of a hand history file
+    --stats <FILE>      File where the player statistics are kept
+    --fps <FPS>         Target frames per second
+    --animation <MS>    Time each card or chip takes to move, 0 turns animations off
+    --theme <THEME>     Colors of the table: classic, four-color, high-contrast or a theme file
+    --colors <DEPTH>    Colors of the terminal: truecolor, 256 or 16, detected by default
+    --ascii             Draw with plain ASCII characters only
+    --help              Print this message";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActorKind {
+    Human, AdHoc, Simple
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SeatConfig {
+    pub name: String,
+    pub actor: ActorKind
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableConfig {
+    pub stack: usize,
+    pub small_blind: usize,
+    pub big_blind: usize,
+    pub ante: usize,
+    pub big_blind_ante: bool,
+    pub cash_game: bool,
+    pub run_it_twice: bool,
+    pub straddle: bool,
+    pub bomb_pot_every: usize,
+    pub bomb_pot_ante: usize,
+    pub buy_in: usize,
+    pub payouts: Vec<usize>,
+    pub seed: Option<u64>,
+    pub fps: u64,
+    pub animation_time: u64, // Milliseconds
+    pub theme: String,
+    pub colors: Option<ColorDepth>, // Detected when not given
+    pub ascii: bool,
+    pub save_file: String,
+    #[serde(skip)]
+    pub resume: bool,
+    pub history_file: String,
+    #[serde(skip)]
+    pub replay: Option<String>,
+    pub stats_file: String,
+    pub seats: Vec<SeatConfig>,
+    pub levels: Vec<BlindLevel>
+}
+
+impl ActorKind {
+    pub fn create(&self) -> Box<dyn PokerActor> {
+        match self {
+            ActorKind::Human => Box::new(HumanActor::new()),
+            ActorKind::AdHoc => Box::new(AdHocActor::new()),
+            ActorKind::Simple => Box::new(SimpleActor::new()),
+        }
+    }
+}
+
+impl SeatConfig {
+    pub fn new(seat: usize) -> Self {
+        let actor = if seat == 0 { ActorKind::Human } else { ActorKind::AdHoc };
+
+        SeatConfig { name: format!("Player {}", seat + 1), actor }
+    }
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        TableConfig {
+            stack: 1000,
+            small_blind: 2,
+            big_blind: 5,
+            ante: 0,
+            big_blind_ante: false,
+            cash_game: false,
+            run_it_twice: false,
+            straddle: false,
+            bomb_pot_every: 0,
+            bomb_pot_ante: 10,
+            buy_in: 10,
+            payouts: vec!(50, 30, 20),
+            seed: None,
+            fps: 15,
+            animation_time: 250,
+            theme: DEFAULT_THEME.into(),
+            colors: None,
+            ascii: false,
+            save_file: DEFAULT_SAVE_PATH.into(),
+            resume: false,
+            history_file: DEFAULT_HISTORY_PATH.into(),
+            replay: None,
+            stats_file: DEFAULT_STATS_PATH.into(),
+            seats: (0..4).map(SeatConfig::new).collect(),
+            levels: vec!()
+        }
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or(format!("Missing value for {flag}"))?;
+
+    value.parse().map_err(|_| format!("Invalid value for {flag}: {value}"))
+}
+
+impl TableConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {e}", path.display()))?;
+
+        toml::from_str(&content).map_err(|e| format!("Unable to parse {}: {e}", path.display()))
+    }
+
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let args = args.collect::<Vec<_>>();
+
+        // The settings file is loaded first so the rest of the flags can override it
+        let config_path = args.iter()
+            .position(|a| a == "--config")
+            .map(|i| args.get(i + 1).cloned().ok_or("Missing value for --config".to_string()))
+            .transpose()?;
+
+        let mut config = match config_path {
+            Some(path) => TableConfig::load(Path::new(&path))?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => TableConfig::load(Path::new(DEFAULT_CONFIG_PATH))?,
+            None => TableConfig::default()
+        };
+
+        let mut args = args.into_iter();
+
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--config" => { args.next(); },
+                "--players" => config.set_players(parse_value(&flag, args.next())?),
+                "--stack" => config.stack = parse_value(&flag, args.next())?,
+                "--seed" => config.seed = Some(parse_value(&flag, args.next())?),
+                "--fps" => config.fps = parse_value(&flag, args.next())?,
+                "--animation" => config.animation_time = parse_value(&flag, args.next())?,
+                "--ante" => config.ante = parse_value(&flag, args.next())?,
+                "--bb-ante" => config.big_blind_ante = true,
+                "--cash" => config.cash_game = true,
+                "--run-it-twice" => config.run_it_twice = true,
+                "--straddle" => config.straddle = true,
+                "--bomb-pot" => config.bomb_pot_every = parse_value(&flag, args.next())?,
+                "--bomb-ante" => config.bomb_pot_ante = parse_value(&flag, args.next())?,
+                "--buy-in" => config.buy_in = parse_value(&flag, args.next())?,
+
+                "--payouts" => {
+                    let value = args.next().ok_or("Missing value for --payouts")?;
+
+                    config.payouts = value.split('/')
+                        .map(|share| parse_value(&flag, Some(share.into())))
+                        .collect::<Result<_, _>>()?;
+                },
+                "--save" => config.save_file = args.next().ok_or("Missing value for --save")?,
+                "--resume" => config.resume = true,
+                "--history" => config.history_file = args.next().ok_or("Missing value for --history")?,
+                "--replay" => config.replay = Some(args.next().ok_or("Missing value for --replay")?),
+                "--stats" => config.stats_file = args.next().ok_or("Missing value for --stats")?,
+                "--theme" => config.theme = args.next().ok_or("Missing value for --theme")?,
+                "--colors" => config.colors = Some(parse_value(&flag, args.next())?),
+                "--ascii" => config.ascii = true,
+
+                "--blinds" => {
+                    let value = args.next().ok_or("Missing value for --blinds")?;
+                    let (sb, bb) = value.split_once('/').ok_or(format!("Invalid value for --blinds: {value}"))?;
+
+                    config.small_blind = parse_value(&flag, Some(sb.into()))?;
+                    config.big_blind = parse_value(&flag, Some(bb.into()))?;
+                },
+
+                "--help" | "-h" => return Err(USAGE.into()),
+
+                _ => return Err(format!("Unknown option: {flag}\n\n{USAGE}"))
+            }
+        }
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn set_players(&mut self, num_players: usize) {
+        self.seats.truncate(num_players);
+
+        while self.seats.len() < num_players {
+            self.seats.push(SeatConfig::new(self.seats.len()));
+        }
+    }
+
+    // Prize of each place, places beyond the number of players share out their percentage among the rest
+    pub fn prizes(&self) -> Vec<usize> {
+        let pool = self.buy_in * self.seats.len();
+        let shares = &self.payouts[..self.payouts.len().min(self.seats.len())];
+        let total = shares.iter().sum::<usize>();
+
+        let mut prizes = shares.iter().map(|share| pool * share / total).collect::<Vec<_>>();
+
+        // Rounding leftovers go to the winner
+        prizes[0] += pool - prizes.iter().sum::<usize>();
+
+        prizes
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_SEATS..=MAX_SEATS).contains(&self.seats.len()) {
+            return Err(format!("A table needs between {MIN_SEATS} and {MAX_SEATS} seats"));
+        }
+
+        if self.seats.iter().skip(1).any(|s| s.actor == ActorKind::Human) {
+            return Err("Only the first seat can be taken by a human".into());
+        }
+
+        if self.small_blind == 0 || self.small_blind > self.big_blind {
+            return Err("The small blind must be positive and not greater than the big blind".into());
+        }
+
+        if self.cash_game && !self.levels.is_empty() {
+            return Err("Cash games are played at fixed blinds, without blind levels".into());
+        }
+
+        if self.bomb_pot_every > 0 && self.bomb_pot_ante == 0 {
+            return Err("Bomb pots need a positive ante".into());
+        }
+
+        if self.payouts.is_empty() || self.payouts.contains(&0) || self.payouts.iter().sum::<usize>() != 100 {
+            return Err("The payouts must be positive percentages adding up to 100".into());
+        }
+
+        for level in &self.levels {
+            level.validate()?;
+        }
+
+        if self.stack == 0 || self.fps == 0 {
+            return Err("The starting stack and the frame rate must be positive".into());
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parses the flags with the given settings file, so that no file in the working directory is picked up
+    fn from_args(name: &str, settings: &str, flags: &str) -> Result<TableConfig, String> {
+        let path = std::env::temp_dir().join(format!("console-ace-config-test-{name}-{}.toml", std::process::id()));
+        fs::write(&path, settings).unwrap();
+
+        let args = ["--config", path.to_str().unwrap()].into_iter().map(String::from).chain(flags.split_whitespace().map(String::from));
+        let config = TableConfig::from_args(args);
+
+        fs::remove_file(&path).unwrap();
+        config
+    }
+
+    #[test]
+    fn flags_override_the_settings_file() {
+        let config = from_args("override", "stack = 500\nsmall_blind = 1\nbig_blind = 2\nante = 1", "--stack 800 --blinds 5/10 --players 6 --payouts 60/40 --bb-ante").unwrap();
+
+        assert_eq!(config.stack, 800);
+        assert_eq!((config.small_blind, config.big_blind, config.ante), (5, 10, 1));
+        assert_eq!(config.payouts, vec!(60, 40));
+        assert!(config.big_blind_ante);
+
+        assert_eq!(config.seats.len(), 6);
+        assert_eq!(config.seats[0].actor, ActorKind::Human);
+        assert!(config.seats[1..].iter().all(|s| s.actor == ActorKind::AdHoc));
+    }
+
+    #[test]
+    fn invalid_flags_are_reported() {
+        assert_eq!(from_args("missing", "", "--stack"), Err("Missing value for --stack".into()));
+        assert_eq!(from_args("invalid", "", "--stack lots"), Err("Invalid value for --stack: lots".into()));
+        assert_eq!(from_args("blinds", "", "--blinds 10"), Err("Invalid value for --blinds: 10".into()));
+        assert!(from_args("unknown", "", "--bogus").unwrap_err().starts_with("Unknown option: --bogus"));
+        assert!(from_args("settings", "stack = \"lots\"", "").unwrap_err().starts_with("Unable to parse"));
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        let invalid = [
+            TableConfig { seats: (0..11).map(SeatConfig::new).collect(), ..TableConfig::default() },
+            TableConfig { seats: vec!(SeatConfig::new(1), SeatConfig { actor: ActorKind::Human, ..SeatConfig::new(1) }), ..TableConfig::default() },
+            TableConfig { small_blind: 10, big_blind: 5, ..TableConfig::default() },
+            TableConfig { payouts: vec!(50, 30), ..TableConfig::default() },
+            TableConfig { bomb_pot_every: 5, bomb_pot_ante: 0, ..TableConfig::default() },
+            TableConfig { stack: 0, ..TableConfig::default() }
+        ];
+
+        assert_eq!(TableConfig::default().validate(), Ok(()));
+
+        for config in invalid {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+}
@@ -1,33 +1,154 @@
-use std::io::{stdout, Write};
+use std::{cell::RefCell, io::{stdout, Write}};
 
-use crossterm::{cursor::{Hide, MoveTo, Show}, event::{DisableMouseCapture, EnableMouseCapture}, execute, style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor}, terminal::{Clear, EnterAlternateScreen, LeaveAlternateScreen, SetSize}, ExecutableCommand};
+use crossterm::{cursor::{Hide, MoveTo, Show}, event::{DisableMouseCapture, EnableMouseCapture}, queue, style::{Color, Print, SetBackgroundColor, SetForegroundColor}, terminal::{Clear, EnterAlternateScreen, LeaveAlternateScreen}, ExecutableCommand};
+
+use crate::engine::palette::{to_ascii, ColorDepth};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Cell {
+    char: char,
+    background: Color,
+    font: Color
+}
+
+const BLANK: Cell = Cell { char: ' ', background: Color::Reset, font: Color::Reset };
+
+// Draw calls write into the back buffer, flushing sends only the cells that differ from the front one
+struct Screen {
+    rows: usize,
+    cols: usize,
+    front: Vec<Cell>,
+    back: Vec<Cell>,
+    saved: Vec<Cell>, // Copy of the back buffer that animations are drawn over
+    cursor: (usize, usize),
+    background: Color,
+    font: Color,
+    depth: ColorDepth,
+    ascii: bool
+}
+
+impl Screen {
+    const fn new() -> Self {
+        Screen {
+            rows: 0, cols: 0, front: vec!(), back: vec!(), saved: vec!(), cursor: (0, 0), background: Color::Reset, font: Color::Reset,
+            depth: ColorDepth::TrueColor, ascii: false
+        }
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize) {
+        self.rows = rows;
+        self.cols = cols;
+        self.front = vec![BLANK; rows * cols];
+        self.back = vec![BLANK; rows * cols];
+        self.saved = vec!();
+    }
+
+    fn clear(&mut self) {
+        self.front.fill(BLANK);
+        self.back.fill(BLANK);
+    }
+
+    fn put(&mut self, char: char) {
+        let (row, col) = self.cursor;
+
+        // Whatever falls outside of the screen is clipped
+        if row < self.rows && col < self.cols {
+            let char = if self.ascii { to_ascii(char) } else { char };
+            self.back[row * self.cols + col] = Cell { char, background: self.background, font: self.font };
+        }
+
+        self.cursor.1 += 1;
+    }
+
+    fn present(&mut self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut buffer = vec!();
+        let mut cursor = None;
+        let mut colors = None;
+
+        for (i, (back, front)) in self.back.iter().zip(&mut self.front).enumerate() {
+            if back == front {
+                continue;
+            }
+
+            let pos = (i / self.cols, i % self.cols);
+
+            if cursor != Some(pos) {
+                queue!(buffer, MoveTo(pos.1 as u16, pos.0 as u16))?;
+            }
+
+            let (background, font) = self.depth.convert_pair(back.background, back.font);
+
+            if colors != Some((background, font)) {
+                queue!(buffer, SetBackgroundColor(background), SetForegroundColor(font))?;
+                colors = Some((background, font));
+            }
+
+            queue!(buffer, Print(back.char))?;
+
+            *front = *back;
+            cursor = Some((pos.0, pos.1 + 1));
+        }
+
+        if !buffer.is_empty() {
+            out.write_all(&buffer)?;
+            out.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+thread_local! {
+    static SCREEN: RefCell<Screen> = const { RefCell::new(Screen::new()) };
+}
+
+// Colors and characters are brought down to what the terminal can show
+pub fn set_capabilities(depth: ColorDepth, ascii: bool) {
+    SCREEN.with_borrow_mut(|screen| {
+        screen.depth = depth;
+        screen.ascii = ascii;
+    });
+}
+
+pub fn is_ascii() -> bool {
+    SCREEN.with_borrow(|screen| screen.ascii)
+}
+
+pub fn save_screen() {
+    SCREEN.with_borrow_mut(|screen| screen.saved = screen.back.clone());
+}
+
+// Nothing is restored after a resize, the saved screen no longer fits
+pub fn restore_screen() {
+    SCREEN.with_borrow_mut(|screen| {
+        if screen.saved.len() == screen.back.len() {
+            screen.back.copy_from_slice(&screen.saved);
+        }
+    });
+}
 
 pub fn hide_cursor() {
     let mut out = stdout();
     
     out.execute(Hide).expect("Unable to hide cursor");
-    flush();
 }
 
 pub fn show_cursor() {
     let mut out = stdout();
 
     out.execute(Show).expect("Unable to show cursor");
-    flush();
 }
 
 pub fn enable_mouse_capture() {
     let mut out = stdout();
 
     out.execute(EnableMouseCapture).expect("Unable to enable mouse capture");
-    flush();
 }
 
 pub fn disable_mouse_capture() {
     let mut out = stdout();
 
     out.execute(DisableMouseCapture).expect("Unable to disable mouse capture");
-    flush();
 }
 
 pub fn enter_alternate_screen() {
@@ -46,6 +167,7 @@ pub fn clear() {
     let mut out = stdout();
 
     out.execute(Clear(crossterm::terminal::ClearType::All)).expect("Unable to clear console");
+    SCREEN.with_borrow_mut(Screen::clear);
 }
 
 pub fn clear_section(r0: usize, c0: usize, r1: usize, c1: usize) {
@@ -60,65 +182,52 @@ pub fn clear_section(r0: usize, c0: usize, r1: usize, c1: usize) {
     }
 }
 
+// The terminal is cleared as well, since it reflows whatever was on it
 pub fn resize(rows: usize, cols: usize) {
     let mut out = stdout();
 
-    out.execute(SetSize(cols as u16, rows as u16)).expect("Unable to resize console");
+    out.execute(Clear(crossterm::terminal::ClearType::All)).expect("Unable to clear console");
+    SCREEN.with_borrow_mut(|screen| screen.resize(rows, cols));
 }
 
 pub fn move_cursor(row: usize, col: usize) {
-    let mut out = stdout();
-
-    out.execute(MoveTo(col as u16, row as u16)).expect("Unable to move cursor");
+    SCREEN.with_borrow_mut(|screen| screen.cursor = (row, col));
 }
 
 pub fn write_str(string: &str) {
-    let mut out = stdout();
-    
-    out.execute(Print(string)).expect("Unable to write to console");
+    SCREEN.with_borrow_mut(|screen| string.chars().for_each(|c| screen.put(c)));
 }
 
 pub fn write_char(char: char) {
-    let mut out = stdout();
-    
-    out.execute(Print(char)).expect("Unable to write to console");
+    SCREEN.with_borrow_mut(|screen| screen.put(char));
 }
 
 pub fn write_char_color(char: char, background: Color, font: Color) {
-    let mut out = stdout();
-    
-    execute!(
-        out,
-        SetBackgroundColor(background),
-        SetForegroundColor(font),
-        Print(char),
-        ResetColor
-    ).expect("Unable to write to console");
+    set_color(background, font);
+    write_char(char);
+    set_color(Color::Reset, Color::Reset);
 }
 
 pub fn set_color(background: Color, font: Color) {
-    let mut out = stdout();
-    
-    execute!(
-        out,
-        SetBackgroundColor(background),
-        SetForegroundColor(font),
-    ).expect("Unable to update color");
+    SCREEN.with_borrow_mut(|screen| {
+        screen.background = background;
+        screen.font = font;
+    });
 }
 
 pub fn draw_bar(row: usize, col: usize, len: usize, curr: usize, total: usize, color: Color) {
+    draw_bar_text(row, col, len, curr as f64 / total as f64, &format!(" {}/{}", curr, total), color);
+}
+
+pub fn draw_bar_text(row: usize, col: usize, len: usize, percentage: f64, text: &str, color: Color) {
     move_cursor(row, col);
 
-    let percentage = curr as f64 / total as f64;
-    let sections = (percentage * len as f64).round() as usize;
+    let sections = ((percentage * len as f64).round() as usize).min(len);
     let left = len - sections;
     
-    let value_str = format!(" {}/{}", curr, total);
-    let mut value_chars = value_str.chars();
+    let mut value_chars = text.chars();
 
-    let mut out = stdout();
-
-    execute!(out, SetBackgroundColor(color), SetForegroundColor(Color::Black)).expect("Unable to set color");
+    set_color(color, Color::Black);
 
     for _ in 0..sections {
         if let Some(char) = value_chars.next() {
@@ -129,7 +238,7 @@ pub fn draw_bar(row: usize, col: usize, len: usize, curr: usize, total: usize, c
         }
     }
 
-    execute!(out, SetBackgroundColor(Color::Black), SetForegroundColor(Color::White)).expect("Unable to set color");
+    set_color(Color::Black, Color::White);
 
     for _ in 0..left {
         if let Some(char) = value_chars.next() {
@@ -141,10 +250,11 @@ pub fn draw_bar(row: usize, col: usize, len: usize, curr: usize, total: usize, c
     }
 }
 
+// Sends the changes of the frame to the terminal in a single write
 pub fn flush() {
     let mut out = stdout();
 
-    out.flush().expect("Unable to flush stdout");
+    SCREEN.with_borrow_mut(|screen| screen.present(&mut out)).expect("Unable to write to console");
 }
 
 pub fn draw_h_line(row: usize, col: usize, len: usize) {
@@ -200,8 +310,8 @@ pub fn draw_square(r0: usize, c0: usize, r1: usize, c1: usize) {
     const BR: &str = "┘";
 
     // Dimensions
-    let width = c1.checked_sub(c0).unwrap_or(0);
-    let height = r1.checked_sub(r0).unwrap_or(0);
+    let width = c1.saturating_sub(c0);
+    let height = r1.saturating_sub(r0);
 
     // Top edge
     move_cursor(r0, c0);
@@ -218,9 +328,6 @@ pub fn draw_square(r0: usize, c0: usize, r1: usize, c1: usize) {
     write_str(BL);
     draw_h_line(r1, c0 + 1, width.saturating_sub(1));
     write_str(BR);
-
-    // Finally, flush everything in one go
-    flush();
 }
 
 pub fn draw_square_double(r0: usize, c0: usize, r1: usize, c1: usize) {
@@ -231,8 +338,8 @@ pub fn draw_square_double(r0: usize, c0: usize, r1: usize, c1: usize) {
     const BR: &str = "╝";
 
     // Dimensions
-    let width = c1.checked_sub(c0).unwrap_or(0);
-    let height = r1.checked_sub(r0).unwrap_or(0);
+    let width = c1.saturating_sub(c0);
+    let height = r1.saturating_sub(r0);
 
     // Top edge
     move_cursor(r0, c0);
@@ -249,13 +356,10 @@ pub fn draw_square_double(r0: usize, c0: usize, r1: usize, c1: usize) {
     write_str(BL);
     draw_h_line_double(r1, c0 + 1, width.saturating_sub(1));
     write_str(BR);
-
-    // Finally, flush everything in one go
-    flush();
 }
 
 pub fn draw_titled_square(title: &str, r0: usize, c0: usize, r1: usize, c1: usize) {
-    let width = c1.checked_sub(c0).unwrap_or(0);
+    let width = c1.saturating_sub(c0);
 
     draw_square(r0, c0, r1, c1);
 
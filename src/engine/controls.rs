@@ -104,4 +104,10 @@ impl Controls {
     pub fn is_pressed(&mut self, key: KeyCode) -> bool {
         *self.keys.entry(key).or_insert(false)
     }
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
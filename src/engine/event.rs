@@ -0,0 +1,62 @@
+use crate::{actor::action::Action, engine::rules::ActionError, poker::{card::Card, play::Play}};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Street {
+    PreFlop, Flop, Turn, River
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Blind {
+    Small, Big, Straddle, Ante
+}
+
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    LevelStarted { level: usize, small_blind: usize, big_blind: usize, ante: usize },
+    HandStarted { dealer: usize, small_blind: usize, big_blind: usize, stacks: Vec<usize> }, // Zero for seats not dealt in
+    BombPotStarted { ante: usize },
+    BlindPosted { seat: usize, blind: Blind, amount: usize },
+    HoleCardsDealt { seat: usize, cards: Vec<Card> },
+    ActionRejected { seat: usize, action: Action, error: ActionError },
+    ActionTaken { seat: usize, action: Action, amount: usize },
+    RunoutStarted { hands: Vec<(usize, Vec<Card>)>, boards: usize },
+    StreetDealt { board: usize, street: Street, cards: Vec<Card> },
+    Showdown { board: usize, hands: Vec<(usize, Play)> },
+    UncalledBetReturned { seat: usize, amount: usize },
+    PotAwarded { pot: usize, seat: usize, amount: usize },
+    PlayerEliminated { seat: usize },
+    ChipsAdded { seat: usize, amount: usize },
+    PlayerJoined { seat: usize, name: String },
+    SatOut { seat: usize },
+    SatIn { seat: usize },
+    HandFinished
+}
+
+impl Street {
+    pub fn from_flipped(num_flipped: usize) -> Self {
+        match num_flipped {
+            0 => Street::PreFlop,
+            3 => Street::Flop,
+            4 => Street::Turn,
+            _ => Street::River
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Street::PreFlop => "Pre-flop",
+            Street::Flop => "Flop",
+            Street::Turn => "Turn",
+            Street::River => "River"
+        }
+    }
+
+    pub fn num_flipped(&self) -> usize {
+        match self {
+            Street::PreFlop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5
+        }
+    }
+}
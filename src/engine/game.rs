@@ -1,43 +1,105 @@
-use std::{collections::HashSet, time::Duration};
+use std::{collections::HashSet, fmt::Display, path::Path, time::Duration};
 
-use crossterm::{event::{self, Event, KeyCode, MouseEventKind}, style::Color, terminal::{disable_raw_mode, enable_raw_mode}};
+use crossterm::{event::{self, Event, KeyCode, MouseEventKind}, style::Color, terminal::{self, disable_raw_mode, enable_raw_mode}};
+use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
 
-use crate::{actor::{action::Action, actor::ActorInfo, adhoc::AdHocActor, human::HumanActor}, engine::{console::{clear, clear_section, disable_mouse_capture, draw_square_double, enable_mouse_capture, enter_alternate_screen, hide_cursor, leave_alternate_screen, move_cursor, resize, set_color, show_cursor, write_str}, controls::Controls, player::{Player, BIG_BLIND, SMALL_BLIND}, state::GameState}, poker::{card::{Card, BAIZE, CREAM, DBLUE, DRED}, deck::Deck, play::{analyze_play, Play}}};
+use crate::{actor::actor::SimpleActor, engine::{animation::{slide, Animation, Sprite}, console::{clear, clear_section, draw_bar_text, disable_mouse_capture, draw_square_double, draw_titled_square, enable_mouse_capture, enter_alternate_screen, hide_cursor, leave_alternate_screen, move_cursor, resize, restore_screen, save_screen, set_capabilities, set_color, show_cursor, write_str}, controls::Controls, event::{GameEvent, Street}, history::{append_hand, HandHistory}, palette::{detect_ascii, ColorDepth}, layout::{Layout, Pos, SeatLayout, Side, CARD_HEIGHT, CARD_WIDTH, COLS, MIN_COLS, MIN_ROWS, ROWS}, config::TableConfig, player::Player, replay::Replayer, save::SavedGame, state::GameState, stats::StatsTracker, table::Table, theme::Theme, timer::Timer}, poker::{ai::hands_equity, card::{Card, Suit}, play::Play}};
 
 pub struct Game {
     pub controls: Controls,
-    pub deck: Deck,
-    pub state: GameState,
-    pub players: Vec<Player>,
-    pub board: Vec<Card>,
-    pub dealer: usize,
-    pub current_bet: usize,
-    pub last_raise: usize
+    pub table: Table,
+    pub layout: Layout,
+    pub history: HandHistory,
+    pub stats: StatsTracker,
+    pub stats_screen: Option<bool>, // Whether it has been drawn
+    pub theme: Theme,
+    pub replay: Option<Replayer>,
+    pub animation: Option<Animation>, // The table waits while it plays
+    pub runout_timer: Timer,
+    pub equities: Vec<(usize, f32)>,
+    pub equities_street: Option<(usize, GameState)>, // Hand number and runout state they were computed for
+    pub config: TableConfig
 }
 
-impl Game {
-    pub fn new() -> Self {
-        let players = vec!(
-            Player::new("Player 1".into(), 1000, Box::new(HumanActor::new())),
-            Player::new("Player 2".into(), 1000, Box::new(AdHocActor::new())),
-            Player::new("Player 3".into(), 1000, Box::new(AdHocActor::new())),
-            Player::new("Player 4".into(), 1000, Box::new(AdHocActor::new())),
-        );
+// Runouts are checked one by one from the flop, this is only for the pre-flop
+const EQUITY_ITERS: usize = 20000;
+
+// Time each street of an all-in runout stays on the table
+const RUNOUT_DELAY: Duration = Duration::from_millis(1500);
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th"
+    };
 
+    format!("{n}{suffix}")
+}
+
+impl Game {
+    pub fn new(config: &TableConfig) -> Self {
         Game { 
             controls: Controls::new(),
-            deck: Deck::new(),
-            state: GameState::MainMenu(false),
-            players,
-            board: vec!(),
-            dealer: 0,
-            current_bet: 0,
-            last_raise: 0
+            table: Table::from_config(config),
+            layout: Layout::new(config.seats.len(), ROWS, COLS),
+            history: HandHistory::new(config.seats.iter().map(|s| s.name.clone()).collect()),
+            stats: StatsTracker::new(config.seats.iter().map(|s| s.name.clone()).collect()),
+            stats_screen: None,
+            theme: Theme::default(),
+            replay: None,
+            animation: None,
+            runout_timer: Timer::new(RUNOUT_DELAY),
+            equities: vec!(),
+            equities_street: None,
+            config: config.clone()
+        }
+    }
+
+    pub fn save(&mut self) -> Result<(), String> {
+        // There is nothing to resume outside of a game
+        if matches!(self.table.state, GameState::MainMenu(_) | GameState::End(_)) {
+            return Ok(());
+        }
+
+        let saved = SavedGame { config: self.config.clone(), table: self.table.save() };
+
+        saved.write(Path::new(&self.config.save_file))
+    }
+
+    pub fn load(&mut self) -> Result<(), String> {
+        let mut saved = SavedGame::load(Path::new(&self.config.save_file))?;
+        saved.config.save_file = self.config.save_file.clone();
+        saved.config.stats_file = self.config.stats_file.clone();
+        saved.config.theme = self.config.theme.clone();
+        saved.config.colors = self.config.colors;
+        saved.config.ascii = self.config.ascii;
+        saved.config.animation_time = self.config.animation_time;
+
+        self.table = Table::from_saved(&saved.config, saved.table);
+        self.layout = Layout::new(saved.config.seats.len(), self.layout.rows, self.layout.cols);
+        self.reset_observers();
+
+        self.config = saved.config;
+
+        self.redraw_table();
+        self.discard_save();
+
+        Ok(())
+    }
+
+    // A game that was resumed or is over can not be resumed again
+    fn discard_save(&mut self) {
+        if let Err(msg) = SavedGame::remove(Path::new(&self.config.save_file)) {
+            self.print_msg(msg);
         }
     }
 
     pub fn startup(&mut self) {
-        resize(41, 125);
+        set_capabilities(self.config.colors.unwrap_or_else(ColorDepth::detect), self.config.ascii || detect_ascii());
 
         enable_raw_mode().expect("Unable to start raw mode");
         enter_alternate_screen();
@@ -47,9 +109,60 @@ impl Game {
         clear();
         hide_cursor();
 
+        let (cols, rows) = terminal::size().expect("Unable to read the console size");
+        self.resize(rows as usize, cols as usize);
+
         self.draw_ui();
     }
 
+    // The table is laid out again for the new size and everything on screen is drawn from scratch
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        resize(rows, cols);
+        self.animation = None;
+        self.layout = Layout::new(self.table.players.len(), rows, cols);
+
+        if self.layout.too_small() {
+            return self.draw_too_small();
+        }
+
+        if let Some(replay) = &mut self.replay {
+            replay.changed = true;
+            return;
+        }
+
+        if self.stats_screen.is_some() {
+            self.stats_screen = Some(false);
+            return;
+        }
+
+        match self.table.state {
+            GameState::MainMenu(_) => self.table.state = GameState::MainMenu(false),
+            GameState::End(_) if self.table.cash.is_some() => self.draw_session_results(),
+            GameState::End(_) => self.draw_results(),
+
+            _ => {
+                self.redraw_table();
+                self.draw_equities();
+                self.draw_cash_options();
+            }
+        }
+    }
+
+    pub fn draw_too_small(&self) {
+        let lines = [
+            "Terminal too small".to_string(),
+            format!("{}x{}, the table needs at least {MIN_COLS}x{MIN_ROWS}", self.layout.cols, self.layout.rows)
+        ];
+
+        set_color(Color::Black, Color::White);
+        clear_section(0, 0, self.layout.rows, self.layout.cols);
+
+        for (i, line) in lines.iter().enumerate() {
+            move_cursor(self.layout.rows / 2 + i, (self.layout.cols / 2).saturating_sub(line.len() / 2));
+            write_str(line);
+        }
+    }
+
     pub fn finalize(&self) {
         move_cursor(0, 0);
         show_cursor();
@@ -71,6 +184,8 @@ impl Game {
                     }    
                 }
 
+                Event::Resize(cols, rows) => self.resize(rows as usize, cols as usize),
+
                 Event::Mouse(me) => {
                     self.controls.set_mouse(me.row as usize, me.column as usize);
 
@@ -93,34 +208,123 @@ impl Game {
 
     }
 
+    pub fn start_replay(&mut self, path: &Path) -> Result<(), String> {
+        self.replay = Some(Replayer::load(path)?);
+        self.controls.release_all();
+
+        Ok(())
+    }
+
+    // Leaves the replay for the main menu, with a fresh table
+    pub fn stop_replay(&mut self) {
+        self.replay = None;
+        self.reset_table();
+    }
+
+    pub fn reset_table(&mut self) {
+        self.table = Table::from_config(&self.config);
+        self.layout = Layout::new(self.config.seats.len(), self.layout.rows, self.layout.cols);
+        self.reset_observers();
+    }
+
+    // The history and the stats follow the players seated at the table
+    fn reset_observers(&mut self) {
+        let names = self.table.players.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+
+        // Stats are kept by name, so the ones already loaded still apply
+        let players = std::mem::take(&mut self.stats.players);
+        self.stats = StatsTracker::new(names.clone());
+        self.stats.players = players;
+
+        self.history = HandHistory::new(names);
+    }
+
+    // The session ends with the chips the human has in front of them
+    pub fn leave_table(&mut self) {
+        self.table.state = GameState::End(self.table.players[0].profit() >= 0);
+        self.draw_session_results();
+        self.discard_save();
+    }
+
+    pub fn redraw_table(&self) {
+        self.draw_baize();
+
+        self.draw_player_chips();
+        self.draw_player_bets();
+        self.draw_dealer_chip();
+
+        if let Some(showdown) = &self.table.showdown {
+            self.draw_player_plays(&showdown.plays, &showdown.winners, &showdown.contenders);
+        }
+    }
+
     pub fn draw_baize(&self) {
-        set_color(BAIZE, Color::Black);
-        clear_section(0, 0, 40, 125);
+        set_color(self.theme.felt, self.theme.text);
+        clear_section(0, 0, self.layout.rows - 1, self.layout.cols - 1);
     }
 
-    pub fn draw_win_text(&self, won: bool) {
-        let txt =  if won { 
-            "
-            ╻ ╻┏━┓╻ ╻   ╻ ╻┏━┓┏┓╻╻
-            ┗┳┛┃ ┃┃ ┃   ┃╻┃┃ ┃┃┗┫╹
-             ╹ ┗━┛┗━┛   ┗┻┛┗━┛╹ ╹╹
-            " 
-        } else {
-            "
-            ╻ ╻┏━┓╻ ╻   ╻  ┏━┓┏━┓╺┳╸
-            ┗┳┛┃ ┃┃ ┃   ┃  ┃ ┃┗━┓ ┃ 
-             ╹ ┗━┛┗━┛   ┗━╸┗━┛┗━┛ ╹ 
-            "
-        };
+    // Table of results over the baize, the row of the human is highlighted
+    pub fn draw_results_table(&self, title: &str, summary: &str, header: &str, rows: Vec<(usize, String)>) {
+        const TOP: usize = 6;
 
-        let width = 22 + (!won as usize) * 2;
+        let width = rows.iter().map(|r| r.1.len()).chain([header.len(), summary.len()]).max().unwrap_or(0) + 4;
+        let col = self.layout.cols / 2 - width / 2;
+        let top = self.layout.origin.row + TOP;
 
-        set_color(Color::Black, Color::White);
+        self.draw_baize();
+
+        set_color(self.theme.felt, self.theme.text);
+        draw_titled_square(title, top, col, top + 8 + rows.len(), col + width);
 
-        for (i, line) in txt.lines().skip(1).enumerate() {
-            move_cursor(19 + i, (125 / 2) - (width / 2));
-            write_str(&line.chars().skip(12).collect::<String>());
+        move_cursor(top + 3, col + width / 2 - summary.len() / 2);
+        write_str(summary);
+
+        move_cursor(top + 5, col + 2);
+        write_str(header);
+
+        for (i, (seat, row)) in rows.iter().enumerate() {
+            set_color(self.theme.felt, if *seat == 0 { self.theme.highlight } else { self.theme.text });
+            move_cursor(top + 7 + i, col + 2);
+            write_str(row);
         }
+
+        self.draw_menu_message("[Enter] Back to the menu");
+    }
+
+    pub fn draw_results(&self) {
+        let prizes = self.config.prizes();
+        let standings = self.table.standings();
+
+        let rows = standings.iter().enumerate()
+            .map(|(place, seat)| {
+                let prize = prizes.get(place).map_or(String::new(), usize::to_string);
+                (*seat, format!("{:<7}{:<24}{:>8}", ordinal(place + 1), self.table.players[*seat].name, prize))
+            })
+            .collect();
+
+        let place = standings.iter().position(|s| *s == 0).unwrap_or(0);
+
+        let summary = match prizes.get(place) {
+            Some(prize) => format!("You finished {} and won {prize}", ordinal(place + 1)),
+            None => format!("You finished {}", ordinal(place + 1))
+        };
+
+        self.draw_results_table("Results", &summary, "Place  Player                     Prize", rows);
+    }
+
+    pub fn draw_session_results(&self) {
+        let rows = (0..self.table.players.len())
+            .sorted_by_key(|s| std::cmp::Reverse(self.table.players[*s].profit()))
+            .map(|seat| {
+                let player = &self.table.players[seat];
+                (seat, format!("{:<24}{:>10}{:>10}", player.name, player.money, format!("{:+}", player.profit())))
+            })
+            .collect();
+
+        let player = &self.table.players[0];
+        let summary = format!("You leave with {} chips, {:+} in this session", player.money, player.profit());
+
+        self.draw_results_table("Session", &summary, "Player                       Chips    Profit", rows);
     }
 
     pub fn draw_start_text(&self) {
@@ -132,14 +336,73 @@ impl Game {
 
         const WIDTH: usize = 60;
 
-        set_color(BAIZE, Color::White);
+        set_color(self.theme.felt, self.theme.text);
 
         for (i, line) in txt.trim_start().lines().enumerate() {
-            move_cursor(28 + i, (125 / 2) - (WIDTH / 2));
+            move_cursor(self.layout.origin.row + 28 + i, self.layout.cols / 2 - WIDTH / 2);
             write_str(line.trim());
         }
     }
 
+    pub fn draw_menu_options(&self) {
+        let mut options = vec!("[Enter] New game");
+
+        if Path::new(&self.config.save_file).exists() {
+            options.push("[L] Resume saved game");
+        }
+
+        if Path::new(&self.config.history_file).exists() {
+            options.push("[H] Replay hands");
+        }
+
+        if !self.stats.players.is_empty() {
+            options.push("[S] Statistics");
+        }
+
+        self.draw_menu_message(&options.join("   "));
+    }
+
+    pub fn draw_menu_message(&self, msg: &str) {
+        set_color(self.theme.felt, self.theme.text);
+        let row = self.layout.origin.row + 34;
+        clear_section(row - 1, 0, row + 1, self.layout.cols - 1);
+
+        self.draw_info_at(row, (self.layout.cols / 2).saturating_sub(msg.len() / 2 + 1), vec!(msg.into()));
+    }
+
+    pub fn draw_stats(&self) {
+        const HEADER: &str = "Player                 Hands    VPIP     PFR   3-Bet      AF    WTSD    W$SD   bb/100";
+        const MAX_ROWS: usize = 25;
+
+        let width = HEADER.len() + 4;
+        let col = self.layout.cols / 2 - width / 2;
+        let top = self.layout.origin.row;
+
+        self.draw_baize();
+
+        set_color(self.theme.felt, self.theme.text);
+        draw_titled_square("Statistics", top + 4, col, top + 10 + MAX_ROWS, col + width);
+
+        move_cursor(top + 7, col + 2);
+        write_str(HEADER);
+
+        // Most played first
+        let mut players = self.stats.players.iter().collect::<Vec<_>>();
+        players.sort_by(|a, b| b.1.hands.cmp(&a.1.hands).then_with(|| a.0.cmp(b.0)));
+
+        for (i, (name, stats)) in players.into_iter().take(MAX_ROWS).enumerate() {
+            let name = name.chars().take(20).collect::<String>();
+
+            move_cursor(top + 9 + i, col + 2);
+            write_str(&format!(
+                "{name:<20} {:>7} {:>7.1} {:>7.1} {:>7.1} {:>7.2} {:>7.1} {:>7.1} {:>8.1}",
+                stats.hands, stats.vpip(), stats.pfr(), stats.three_bet(), stats.aggression_factor(), stats.wtsd(), stats.wsd(), stats.bb_per_100()
+            ));
+        }
+
+        self.draw_menu_message("[Q] Back to the menu");
+    }
+
     pub fn draw_logo(&self) {
         let txt = "
                █████████  ██████   ██████ ██████████        █████████                    
@@ -154,97 +417,77 @@ impl Game {
 
         const WIDTH: usize = 77;
 
-        set_color(BAIZE, Color::White);
-        draw_square_double(8, 20, 19, 104);
+        let top = self.layout.origin.row;
+        let center = self.layout.cols / 2;
+
+        set_color(self.theme.felt, self.theme.text);
+        draw_square_double(top + 8, center - 42, top + 19, center + 42);
 
         for (i, line) in txt.trim_end().lines().skip(1).enumerate() {
-            set_color(BAIZE, DRED);
-            move_cursor(10 + i, (125 / 2) - (WIDTH / 2));
+            set_color(self.theme.felt, self.theme.accent);
+            move_cursor(top + 10 + i, center - WIDTH / 2);
             write_str(&line[12..].chars().take(45).collect::<String>());
 
-            set_color(BAIZE, Color::White);
+            set_color(self.theme.felt, self.theme.text);
             write_str(&line[12..].chars().skip(45).collect::<String>());
         }
     }
 
-    pub fn draw_single_player_play(&self, col: usize, row: usize, play: String) {
-        let name = format!(" {} ", play);
-        let h = name.len() / 2;
-
-        set_color(DBLUE, Color::White);
-        move_cursor(row, col - h);
-        write_str(&name);
+    fn play_col(&self, seat: &SeatLayout, len: usize) -> usize {
+        let (first, last) = (self.layout.origin.col, self.layout.origin.col + self.layout.width - len);
 
-        set_color(BAIZE, DBLUE);
-        move_cursor(row - 1, col - h);
-        write_str(&"▄".repeat(name.len()));
-        move_cursor(row + 1, col - h);
-        write_str(&"▀".repeat(name.len()));
+        // The compact layout puts the labels of the side seats over their cards, growing outwards
+        match (seat.side, self.layout.compact) {
+            (Side::Left, false) | (Side::Right, true) => seat.play.col.min(last),
+            (Side::Right, false) => seat.play.col - len,
+            (Side::Left, true) => seat.play.col.saturating_sub(len).max(first),
+            _ => seat.play.col.saturating_sub(len / 2).min(last)
+        }
     }
 
-    pub fn draw_single_player_play_left(&self, col: usize, row: usize, play: String) {
+    pub fn draw_single_player_play(&self, seat: &SeatLayout, play: String) {
         let name = format!(" {} ", play);
+        let len = name.chars().count();
+
+        let col = self.play_col(seat, len);
+        let row = seat.play.row;
 
-        set_color(DBLUE, Color::White);
+        set_color(self.theme.label, self.theme.label_text);
         move_cursor(row, col);
         write_str(&name);
 
-        set_color(BAIZE, DBLUE);
+        set_color(self.theme.felt, self.theme.label);
         move_cursor(row - 1, col);
-        write_str(&"▄".repeat(name.len()));
+        write_str(&"▄".repeat(len));
         move_cursor(row + 1, col);
-        write_str(&"▀".repeat(name.len()));
+        write_str(&"▀".repeat(len));
     }
 
-    pub fn draw_single_player_play_right(&self, col: usize, row: usize, play: String) {
-        let name = format!(" {} ", play);
-
-        set_color(DBLUE, Color::White);
-        move_cursor(row, col - name.len());
-        write_str(&name);
-
-        set_color(BAIZE, DBLUE);
-        move_cursor(row - 1, col - name.len());
-        write_str(&"▄".repeat(name.len()));
-        move_cursor(row + 1, col - name.len());
-        write_str(&"▀".repeat(name.len()));
-    }
+    pub fn draw_player_plays(&self, plays: &[Play], winners: &HashSet<usize>, valid_players: &HashSet<usize>) {
+        for (i, seat) in self.layout.seats.iter().enumerate() {
+            if !valid_players.contains(&i) {
+                continue;
+            }
 
-    pub fn draw_player_plays(&self, plays: &Vec<Play>, winners: &HashSet<usize>, valid_players: &HashSet<usize>) {
-        let play_str = |i: usize| {
-            if winners.contains(&i) {
+            let play = if winners.contains(&i) {
                 format!(">>> {} <<<", plays[i].name())
 
             } else {
                 plays[i].name()
-            }
-        }; 
-
-        if valid_players.contains(&0) {
-            self.draw_single_player_play(62, 27, play_str(0));
-        }
-
-        if valid_players.contains(&1) {
-            self.draw_single_player_play_left(5, 9, play_str(1));
-        }
+            };
 
-        if valid_players.contains(&2) {
-            self.draw_single_player_play(62, 13, play_str(2));
-        }
-
-        if valid_players.contains(&3) {
-            self.draw_single_player_play_right(120, 31, play_str(3));
+            self.draw_single_player_play(seat, play);
         }
     }
 
     pub fn draw_single_player_chips(&self, col: usize, row: usize, player: &Player) {
-        set_color(CREAM, Color::Black);
+        set_color(self.theme.chips, self.theme.chips_text);
         clear_section(row, col, row, col + 11);
 
         move_cursor(row, col + 1);
         write_str(&format!("Chips {:>4}", player.money));
 
-        set_color(BAIZE, CREAM);
+        set_color(self.theme.felt, self.theme.chips);
         move_cursor(row - 1, col);
         write_str(&"▄".repeat(12));
         move_cursor(row + 1, col);
@@ -252,29 +495,21 @@ impl Game {
     }
 
     pub fn draw_player_chips(&self) {
-        self.draw_single_player_chips(79, 37, &self.players[0]);
-
-        if !self.players[1].lost() {
-            self.draw_single_player_chips(5, 31, &self.players[1]);
-        }
-
-        if !self.players[2].lost() {
-            self.draw_single_player_chips(34, 3, &self.players[2]);
-        }
-
-        if !self.players[3].lost() {
-            self.draw_single_player_chips(109, 9, &self.players[3]);
+        for (i, (player, seat)) in self.table.players.iter().zip(&self.layout.seats).enumerate() {
+            if i == 0 || !player.lost() {
+                self.draw_single_player_chips(seat.chips.col, seat.chips.row, player);
+            }
         }
     }
 
     pub fn draw_single_player_bet(&self, col: usize, row: usize, player: &Player) {
-        set_color(CREAM, Color::Black);
+        set_color(self.theme.chips, self.theme.chips_text);
         clear_section(row, col, row, col + 9);
 
         move_cursor(row, col + 1);
         write_str(&format!("Bet {:>4}", player.bet));
 
-        set_color(BAIZE, CREAM);
+        set_color(self.theme.felt, self.theme.chips);
         move_cursor(row - 1, col);
         write_str(&"▄".repeat(10));
         move_cursor(row + 1, col);
@@ -282,27 +517,19 @@ impl Game {
     }
 
     pub fn draw_player_bets(&self) {
-        self.draw_single_player_bet(79, 34, &self.players[0]);
-
-        if !self.players[1].lost() {
-            self.draw_single_player_bet(5, 34, &self.players[1]);
-        }
-
-        if !self.players[2].lost() {
-            self.draw_single_player_bet(34, 6, &self.players[2]);
-        }
-
-        if !self.players[3].lost() {
-            self.draw_single_player_bet(109, 6, &self.players[3]);
+        for (i, (player, seat)) in self.table.players.iter().zip(&self.layout.seats).enumerate() {
+            if i == 0 || !player.lost() {
+                self.draw_single_player_bet(seat.bet.col, seat.bet.row, player);
+            }
         }
     }
 
     pub fn draw_dealer_chip_at(&self, row: usize, col: usize) {
-        set_color(DBLUE, Color::White);
+        set_color(self.theme.label, self.theme.label_text);
         move_cursor(row, col);
         write_str(" D ");
 
-        set_color(DBLUE, BAIZE);
+        set_color(self.theme.label, self.theme.felt);
         move_cursor(row - 1, col);
         write_str(&"▀".repeat(3));
         move_cursor(row + 1, col);
@@ -310,11 +537,11 @@ impl Game {
     }
 
     pub fn draw_turn_chip_at(&self, row: usize, col: usize) {
-        set_color(Color::Cyan, Color::Black);
+        set_color(self.theme.turn, self.theme.turn_text);
         move_cursor(row, col);
         write_str(" T ");
 
-        set_color(Color::Cyan, BAIZE);
+        set_color(self.theme.turn, self.theme.felt);
         move_cursor(row - 1, col);
         write_str(&"▀".repeat(3));
         move_cursor(row + 1, col);
@@ -322,46 +549,107 @@ impl Game {
     }
 
     pub fn draw_dealer_chip(&self) {
-        set_color(BAIZE, Color::Black);
-        clear_section(30, 43, 32, 46);
-        clear_section(8, 4, 10, 7);
-        clear_section(2, 77, 4, 80);
-        clear_section(30, 108, 32, 111);
+        set_color(self.theme.felt, self.theme.text);
 
-        match self.dealer {
-            0 => self.draw_dealer_chip_at(31, 44),
-            1 => self.draw_dealer_chip_at(9, 5),
-            2 => self.draw_dealer_chip_at(3, 78),
-            3 => self.draw_dealer_chip_at(31, 109),
-            _ => unreachable!()
+        for seat in &self.layout.seats {
+            clear_section(seat.dealer.row - 1, seat.dealer.col, seat.dealer.row + 1, seat.dealer.col + 2);
         }
+
+        let seat = &self.layout.seats[self.table.dealer];
+        self.draw_dealer_chip_at(seat.dealer.row, seat.dealer.col);
     }
 
     pub fn draw_turn_chip(&self, turn: usize) {
-        set_color(BAIZE, Color::Black);
-        clear_section(33, 43, 35, 46);
-        clear_section(8, 9, 10, 12);
-        clear_section(2, 82, 4, 85);
-        clear_section(30, 113, 32, 116);
+        set_color(self.theme.felt, self.theme.text);
 
-        match turn {
-            0 => self.draw_turn_chip_at(34, 44),
-            1 => self.draw_turn_chip_at(9, 10),
-            2 => self.draw_turn_chip_at(3, 83),
-            3 => self.draw_turn_chip_at(31, 114),
-            _ => unreachable!()
+        for seat in &self.layout.seats {
+            clear_section(seat.turn.row - 1, seat.turn.col, seat.turn.row + 1, seat.turn.col + 2);
         }
+
+        let seat = &self.layout.seats[turn];
+        self.draw_turn_chip_at(seat.turn.row, seat.turn.col);
     }
 
-    pub fn bet(&mut self, turn: usize, chips: usize) {
-        self.players[turn].bet_chips(chips);
+    // Equity of every live hand while the board is run out, computed once for each street
+    pub fn draw_equities(&mut self) {
+        let state = self.table.state;
+
+        let GameState::Runout(num_flipped, board) = state else {
+            return;
+        };
 
-        if self.current_bet < self.players[turn].bet {
-            self.current_bet = self.players[turn].bet;
+        let street = Some((self.table.hand_number, state));
+
+        if self.equities_street != street {
+            let seats = self.table.contenders().collect::<Vec<_>>();
+            let hands = seats.iter().map(|s| self.table.players[*s].hand.clone()).collect::<Vec<_>>();
+
+            // A fixed seed keeps the table RNG out of it, so the shown numbers do not change the game
+            let mut rng = StdRng::seed_from_u64(0);
+            let community = &self.table.board_cards(board)[..num_flipped];
+
+            self.equities = seats.into_iter().zip(hands_equity(&hands, community, EQUITY_ITERS, &mut rng)).collect();
+            self.equities_street = street;
         }
 
-        self.draw_player_chips();
-        self.draw_player_bets();
+        for (seat, equity) in &self.equities {
+            self.draw_single_player_play(&self.layout.seats[*seat], format!("Equity {:>5.1}%", equity * 100.0));
+        }
+    }
+
+    pub fn clear_equities(&self) {
+        const LEN: usize = " Equity 100.0% ".len();
+
+        set_color(self.theme.felt, self.theme.text);
+
+        for seat in &self.layout.seats {
+            let col = self.play_col(seat, LEN);
+            clear_section(seat.play.row - 1, col, seat.play.row + 1, col + LEN - 1);
+        }
+    }
+
+    pub fn draw_board_label(&self, label: &str) {
+        let pos = self.layout.board_label;
+
+        // There is no room over the board in the compact layout, so the label goes to the right of the top line
+        if self.layout.compact {
+            let msg = format!(" {label} ");
+
+            set_color(self.theme.felt, self.theme.text);
+            clear_section(pos.row, self.layout.cols / 2, pos.row, pos.col);
+
+            set_color(self.theme.label, self.theme.label_text);
+            move_cursor(pos.row, (pos.col + 1).saturating_sub(msg.len()));
+            write_str(&msg);
+            return;
+        }
+
+        set_color(self.theme.felt, self.theme.text);
+        clear_section(pos.row - 1, 0, pos.row + 1, self.layout.cols - 1);
+
+        self.draw_info_at(pos.row, pos.col.saturating_sub(label.len() / 2 + 1), vec!(label.into()));
+    }
+
+    pub fn draw_level_clock(&self) {
+        let Some(schedule) = &self.table.schedule else {
+            return;
+        };
+
+        let level = schedule.current();
+        let label = if level.ante > 0 {
+            format!(" Level {}  Blinds {}/{}  Ante {} ", schedule.level + 1, level.small_blind, level.big_blind, level.ante)
+        } else {
+            format!(" Level {}  Blinds {}/{} ", schedule.level + 1, level.small_blind, level.big_blind)
+        };
+
+        let pos = self.layout.level;
+
+        set_color(self.theme.label, self.theme.label_text);
+        move_cursor(pos.row, pos.col);
+        write_str(&label);
+
+        let (progress, text) = schedule.progress();
+        draw_bar_text(pos.row, pos.col + label.len(), 20, progress, &format!(" {text}"), self.theme.chips);
     }
 
     pub fn print_msg(&mut self, msg: String) {
@@ -373,14 +661,14 @@ impl Game {
     pub fn draw_info_at(&self, row: usize, col: usize, lines: Vec<String>) {
         let width = lines.iter().map(String::len).max().unwrap();
 
-        set_color(DBLUE, Color::White);
+        set_color(self.theme.label, self.theme.label_text);
 
         for (i, line) in lines.iter().enumerate() {
             move_cursor(row + i, col);
             write_str(&format!(" {}{} ", line, " ".repeat(width - line.len())));
         }
 
-        set_color(BAIZE, DBLUE);
+        set_color(self.theme.felt, self.theme.label);
         move_cursor(row - 1, col);
         write_str(&"▄".repeat(width + 2));
         move_cursor(row + lines.len(), col);
@@ -388,376 +676,708 @@ impl Game {
     }
 
     pub fn clear_info(&self) {
-        set_color(BAIZE, DBLUE);
-        clear_section(30, 23, 41, 40);
+        let row = self.layout.info.row;
+
+        set_color(self.theme.felt, self.theme.label);
+        clear_section(row - 1, 0, row + 3, self.layout.cols - 1);
     }
 
-    pub fn next_turn(&mut self, turn: usize) -> usize {
-        let mut res = (turn + 1) % 4;
+    pub fn draw_info_message(&self, msg: &str) {
+        let info = self.layout.info;
+        let board = &self.layout.board;
+
+        // Only under the board, the labels of the seats around may share these rows
+        set_color(self.theme.felt, self.theme.label);
+        clear_section(info.row - 1, board[0].col, info.row + 1, board[4].col + CARD_WIDTH - 1);
+
+        self.draw_info_at(info.row, info.col.saturating_sub(msg.len() / 2 + 1), vec!(msg.into()));
+    }
+
+    pub fn draw_cash_options(&self) {
+        let Some(cash) = &self.table.cash else {
+            return;
+        };
+
+        // Chips are only added between hands
+        if self.table.state != GameState::Resolving {
+            return;
+        }
+
+        let player = &self.table.players[0];
+        let mut options = vec!("[Enter] Next hand");
+
+        if player.money == 0 {
+            options.push("[U] Rebuy");
+
+        } else if player.money < cash.buy_in {
+            options.push("[U] Top up");
+        }
+
+        if player.sitting_out {
+            options.push("[O] Sit in");
 
-        while self.players[res].lost() {
-            res = (res + 1) % 4;
+        } else if player.money > 0 {
+            options.push("[O] Sit out");
         }
 
-        res
+        options.push("[X] Leave");
+
+        self.draw_info_message(&options.join("   "));
     }
 
-    pub fn perform_action(&mut self, action: Action, turn: usize) {
-        match action {
-            Action::Fold => self.players[turn].fold(),
-            
-            Action::Call => {
-                let call_amount = self.current_bet - self.players[turn].bet;
-                let player_money = self.players[turn].money;
+    pub fn draw_session(&self) {
+        let Some(cash) = &self.table.cash else {
+            return;
+        };
 
-                self.bet(turn, player_money.min(call_amount))
-            },
+        let player = &self.table.players[0];
+        let status = if player.sitting_out { "  Sitting out" } else { "" };
 
-            Action::Raise(c) => {
-                let call_amount = self.current_bet - self.players[turn].bet;
-                let player_money = self.players[turn].money;
+        let label = format!(
+            " Cash game  Blinds {}/{}  Buy-in {}  Session {:<+7}{status} ",
+            self.table.small_blind, self.table.big_blind, cash.buy_in, player.profit()
+        );
 
-                self.last_raise = c;
-                self.bet(turn, player_money.min(call_amount + c));
-            },
+        let pos = self.layout.level;
+
+        set_color(self.theme.felt, self.theme.text);
+        clear_section(pos.row, pos.col, pos.row, pos.col + label.len() + 12);
+
+        set_color(self.theme.label, self.theme.label_text);
+        move_cursor(pos.row, pos.col);
+        write_str(&label);
+    }
+
+    pub fn draw_error(&self, error: &impl Display) {
+        let info = self.layout.info;
+        let msg = format!(" {error} ");
+
+        // Below the second line of the actions, when they take two
+        set_color(self.theme.felt, self.theme.label);
+        clear_section(info.row + 3, 0, info.row + 3, self.layout.cols - 1);
+
+        set_color(self.theme.accent, self.theme.label_text);
+        move_cursor(info.row + 3, info.col.saturating_sub(msg.len() / 2));
+        write_str(&msg);
+    }
+
+    pub fn draw_round(&self, num_flipped: usize, turn: usize, show_hand: bool) {
+        self.draw_turn_chip(turn);
+        self.draw_level_clock();
+        self.draw_session();
+
+        // Center cards
+        for (i, (card, pos)) in self.table.board.iter().zip(&self.layout.board).enumerate() {
+            card.draw(&self.theme, pos.col, pos.row, i >= num_flipped);
+        }
+
+        // Players
+        for (i, (player, seat)) in self.table.players.iter().zip(&self.layout.seats).enumerate() {
+            let back = i != 0 || !show_hand;
+
+            for (card, pos) in player.hand.iter().zip(&seat.cards) {
+                card.draw(&self.theme, pos.col, pos.row, back);
+            }
         }
     }
 
-    pub fn solve_pots(&mut self, plays: &Vec<Play>) -> HashSet<usize> {
-        // Players that won something
-        let mut winners = HashSet::new();
+    // Sprites added together start one after the other, a stagger of one waits for the previous one to finish
+    fn animate(&mut self, sprites: Vec<Sprite>, stagger: f64) {
+        if self.config.animation_time == 0 {
+            return;
+        }
 
-        // Initial contributions
-        let mut contributions = self.players.iter().map(|p| p.bet).collect::<Vec<_>>();
-        let mut total = contributions.iter().sum::<usize>();
-        let valid_plays = plays.iter()
-            .enumerate()
-            .filter(|(i, _)| !self.players[*i].folded)
-            .filter(|(i, _)| !self.players[*i].lost())
-            .collect::<Vec<_>>();
+        let duration = Duration::from_millis(self.config.animation_time);
+        let animation = self.animation.get_or_insert_with(Animation::new);
+        let start = animation.end();
 
-        // Reset player bets
-        self.players.iter_mut().for_each(Player::lose_bet);
+        for (i, sprite) in sprites.into_iter().enumerate() {
+            animation.add(sprite, start + duration.mul_f64(stagger * i as f64), duration);
+        }
+    }
+
+    // Cards slide from the dealer button, one to each seat and then a second one, before the board
+    fn deal_cards(&mut self) {
+        let dealer = self.layout.seats[self.table.dealer].dealer;
+        let from = Pos { row: dealer.row.saturating_sub(CARD_HEIGHT / 2), col: dealer.col.saturating_sub(CARD_WIDTH / 2 - 1) };
+
+        let num_seats = self.table.players.len();
+        let seats = (1..=num_seats)
+            .map(|i| (self.table.dealer + i) % num_seats)
+            .filter(|s| !self.table.players[*s].hand.is_empty())
+            .collect::<Vec<_>>();
 
-        // Pot winning algorithm
-        while total > 0 {
-            // Calculate layers
-            let mut layers = contributions.clone();
-            layers.sort();
-            layers.dedup();
+        let mut sprites = vec!();
 
-            // Compute incremental tiers
-            for i in (1..layers.len()).rev() {
-                layers[i] -= layers[i - 1]; 
+        for i in 0..2 {
+            for seat in &seats {
+                let card = self.table.players[*seat].hand[i].clone();
+                sprites.push(Sprite::Card { card, back: true, from, to: self.layout.seats[*seat].cards[i] });
             }
+        }
 
-            // Solve layers from lowest amount to highest
-            for layer in layers {
-                // Get players of the layer (non-folded and active)
-                let mut layer_players = valid_plays.iter()
-                    .filter(|(p, _)| contributions[*p] >= layer)
-                    .collect::<Vec<_>>();
-
-                layer_players.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
-
-                // Get tied best players of the layer
-                let (_, best_play) = layer_players.last().unwrap();
-                let tied_best_players = layer_players.iter()
-                    .filter(|(_, p)| p == best_play)
-                    .map(|i| i.0)
-                    .collect::<Vec<_>>();
-
-                // Subtract layer value from contributions to get remaining contributions
-                let mut layer_amount = 0;
-
-                for c in contributions.iter_mut() {
-                    let amount = layer.min(*c);
-                    *c -= amount;
-                    layer_amount += amount;
-                }
+        for (card, to) in self.table.board.iter().zip(&self.layout.board) {
+            sprites.push(Sprite::Card { card: card.clone(), back: true, from, to: *to });
+        }
 
-                total -= layer_amount;
+        self.animate(sprites, 1.0 / 3.0);
+    }
 
-                // Distribute the amount got from this layer
-                let base_amount = layer_amount / tied_best_players.len();
-                let mut remainder = layer_amount % tied_best_players.len();
+    fn flip_cards(&mut self, street: Street, cards: &[Card]) {
+        let first = street.num_flipped() - cards.len();
 
-                for p in tied_best_players.iter() {
-                    let won_amount = base_amount + 1.min(remainder);
-                    self.players[*p].win(won_amount);
+        let sprites = cards.iter().zip(&self.layout.board[first..])
+            .map(|(card, at)| Sprite::Flip { card: card.clone(), at: *at })
+            .collect();
 
-                    if remainder > 0 {
-                        remainder -= 1; // Remainder is distributed in seating order
-                    }
+        self.animate(sprites, 1.0);
+    }
 
-                    if won_amount > 0 {
-                        winners.insert(*p);
-                    }
+    // Bets slide into the pot before it is handed out
+    fn collect_bets(&mut self, contributions: &[usize]) {
+        if self.config.animation_time == 0 {
+            return;
+        }
+
+        let total = contributions.iter().sum::<usize>();
+
+        let sprites = contributions.iter().enumerate()
+            .filter(|(_, amount)| **amount > 0)
+            .map(|(seat, amount)| Sprite::Chips { amount: *amount, from: self.layout.seats[seat].bet, to: self.pot_pos(total) })
+            .collect();
+
+        self.animate(sprites, 0.0);
+        self.draw_player_bets();
+    }
+
+    // Chips in the pot sit centered under the board
+    fn pot_pos(&self, amount: usize) -> Pos {
+        let width = amount.to_string().len() + 2;
+
+        Pos { row: self.layout.info.row, col: self.layout.info.col - width / 2 }
+    }
+
+    // The table is drawn back as it was, with the labels brought up to date
+    fn finish_animation(&mut self) {
+        self.animation = None;
+
+        restore_screen();
+        self.draw_player_chips();
+        self.draw_player_bets();
+    }
+
+    fn draw_animation(&self, animation: &Animation) {
+        restore_screen();
+
+        for (sprite, progress) in animation.frames() {
+            match sprite {
+                Sprite::Card { card, back, from, to } => {
+                    let pos = slide(*from, *to, progress);
+                    card.draw(&self.theme, pos.col, pos.row, *back);
+                },
+
+                Sprite::Flip { card, at } => {
+                    self.draw_flip(card, *at, progress);
+                },
+
+                Sprite::Chips { .. } if progress >= 1.0 => {},
+
+                Sprite::Chips { amount, from, to } => {
+                    let pos = slide(*from, *to, progress);
+                    self.draw_chips_at(pos.row, pos.col, *amount);
                 }
             }
         }
+    }
+
+    // The card narrows showing its back, then widens again showing its face
+    fn draw_flip(&self, card: &Card, at: Pos, progress: f64) {
+        if progress >= 1.0 {
+            return card.draw(&self.theme, at.col, at.row, false);
+        }
+
+        let (color, scale) = if progress < 0.5 {
+            (self.theme.card_back, 1.0 - 2.0 * progress)
+
+        } else {
+            (self.theme.card_face, 2.0 * progress - 1.0)
+        };
+
+        let width = ((CARD_WIDTH as f64 * scale).round() as usize).max(1);
+        let col = at.col + (CARD_WIDTH - width) / 2;
+
+        set_color(self.theme.felt, self.theme.text);
+        clear_section(at.row, at.col, at.row + CARD_HEIGHT - 1, at.col + CARD_WIDTH - 1);
+
+        set_color(color, self.theme.text);
+        clear_section(at.row + 1, col, at.row + CARD_HEIGHT - 2, col + width - 1);
+    }
+
+    pub fn draw_chips_at(&self, row: usize, col: usize, amount: usize) {
+        let label = format!(" {amount} ");
+
+        set_color(self.theme.chips, self.theme.chips_text);
+        move_cursor(row, col);
+        write_str(&label);
+
+        set_color(self.theme.felt, self.theme.chips);
+        move_cursor(row - 1, col);
+        write_str(&"▄".repeat(label.len()));
+        move_cursor(row + 1, col);
+        write_str(&"▀".repeat(label.len()));
+    }
+
+    pub fn draw_actions_info(&self) {
+        let legal = self.table.legal_actions(0);
+
+        let raise_bet = if legal.opened { "Raise to" } else { "Bet" };
+        let raise_all_in = |to: usize| {
+            if legal.is_all_in(to) {
+                "All-in".into()
+            } else {
+                format!("{raise_bet} {to}")
+            }
+        };
+
+        let mut options = vec!(
+            if legal.call == 0 {
+                "[C] Check".into()
+            
+            } else if legal.all_in_raises {
+                format!("[C] Call {}", legal.call)
+
+            } else {
+                "[C] All-in".into()
+            }
+        );
+
+        // Raises are left out when betting is not open to the player
+        if legal.can_raise {
+            let keys = ["[R]", "[D]", "[T]", "[B+D]", "[B+T]"];
+
+            options.extend(keys.iter()
+                .zip(legal.raise_presets())
+                .map(|(key, to)| format!("{key} {}", raise_all_in(to))));
+        }
+
+        options.push("[F] Fold".into());
+
+        // Options wrap to a second line when they do not fit across the table
+        let max_width = self.layout.width - 4;
+        let mut lines: Vec<String> = vec!();
+
+        for option in options {
+            match lines.last_mut() {
+                Some(line) if line.len() + 3 + option.len() <= max_width => {
+                    line.push_str("   ");
+                    line.push_str(&option);
+                },
+
+                _ => lines.push(option)
+            }
+        }
+
+        let width = lines.iter().map(String::len).max().unwrap_or(0);
+        let info = self.layout.info;
+
+        self.draw_info_at(info.row, info.col.saturating_sub(width / 2 + 1), lines);
+    }
+
+    // Keys are consumed once read, so holding them repeats the action at a steady pace
+    fn key_pressed(&mut self, key: KeyCode) -> bool {
+        if self.controls.is_pressed(key) && !self.controls.is_locked(key) {
+            self.controls.release(key);
+            self.controls.lock(key, Duration::from_millis(150));
+            return true;
+        }
+
+        false
+    }
+
+    fn enter_pressed(&mut self) -> bool {
+        if self.controls.is_pressed(KeyCode::Enter) && !self.controls.is_locked(KeyCode::Enter) {
+            self.controls.lock(KeyCode::Enter, Duration::from_millis(500));
+            return true;
+        }
+
+        false
+    }
+
+    pub fn update_replay(&mut self) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+
+        let keys = [
+            KeyCode::Right, KeyCode::Left, KeyCode::Down, KeyCode::Up, KeyCode::Char('n'), KeyCode::Char('p'),
+            KeyCode::Char(' '), KeyCode::Char('+'), KeyCode::Char('-'), KeyCode::Char('q')
+        ];
+
+        let pressed = keys.into_iter().find(|k| self.controls.is_pressed(*k) && !self.controls.is_locked(*k));
+
+        if let Some(key) = pressed {
+            self.controls.release(key);
+            self.controls.lock(key, Duration::from_millis(150));
+
+            match key {
+                KeyCode::Right => replay.step_forward(),
+                KeyCode::Left => replay.step_back(),
+                KeyCode::Down => replay.next_street(),
+                KeyCode::Up => replay.previous_street(),
+                KeyCode::Char('n') => replay.next_hand(),
+                KeyCode::Char('p') => replay.previous_hand(),
+                KeyCode::Char(' ') => replay.toggle_play(),
+                KeyCode::Char('+') => replay.faster(),
+                KeyCode::Char('-') => replay.slower(),
+                _ => return self.stop_replay()
+            }
+        }
 
-        winners
+        replay.update();
     }
 
     pub fn update(&mut self) -> bool {
-        match self.state {
-            GameState::MainMenu(_) => {
-                if self.controls.is_pressed(KeyCode::Enter) {
-                    self.draw_baize();
-                    self.state = GameState::Dealing;
+        // The game waits until the table fits again
+        if self.layout.too_small() {
+            return false;
+        }
+
+        if self.replay.is_some() {
+            self.update_replay();
+            return false;
+        }
+
+        if self.stats_screen.is_some() {
+            if self.key_pressed(KeyCode::Char('q')) {
+                self.stats_screen = None;
+                self.table.state = GameState::MainMenu(false);
+            }
+
+            return false;
+        }
+
+        if self.animation.is_some() {
+            if self.key_pressed(KeyCode::Char(' ')) && let Some(animation) = &mut self.animation {
+                animation.skip();
+            }
+
+            if !self.animation.as_ref().is_some_and(Animation::done) {
+                return false;
+            }
+
+            self.finish_animation();
+        }
+
+        let prev_state = self.table.state;
+
+        match prev_state {
+            GameState::MainMenu(_) if self.controls.is_pressed(KeyCode::Enter) => {
+                self.draw_baize();
+                self.table.start();
+            },
+
+            GameState::MainMenu(_) if self.controls.is_pressed(KeyCode::Char('l')) => {
+                if let Err(msg) = self.load() {
+                    self.controls.release_all();
+                    self.draw_menu_message(&msg);
                 }
             },
-            
-            GameState::Dealing => {
-                // Prepare players
-                self.players.iter_mut().for_each(Player::unfold);
-
-                // Prepare cards
-                self.deck.shuffle();
-
-                for player in &mut self.players {
-                    if !player.lost() {
-                        for _ in 0..2 {
-                            player.give_card(self.deck.pop().expect("No more cards"));
-                        }
-                    }
+
+            GameState::MainMenu(_) if self.key_pressed(KeyCode::Char('s')) && !self.stats.players.is_empty() => {
+                self.stats_screen = Some(false);
+            },
+
+            GameState::MainMenu(_) if self.key_pressed(KeyCode::Char('h')) => {
+                let path = self.config.history_file.clone();
+
+                if let Err(msg) = self.start_replay(Path::new(&path)) {
+                    self.draw_menu_message(&msg);
                 }
+            },
 
-                for _ in 0..5 {
-                    self.board.push(self.deck.pop().expect("No more cards"));
+            GameState::Round(_, 0, true, true, initial) if self.table.can_act(0, initial) => {
+                self.draw_actions_info();
+            },
+
+            GameState::Straddle(0) => {
+                self.draw_info_message(&format!("[S] Straddle {}   [N] No straddle", 2 * self.table.big_blind));
+            },
+
+            GameState::Resolving if self.table.cash.is_some() && self.key_pressed(KeyCode::Char('u')) => {
+                if let Err(msg) = self.table.top_up(0) {
+                    self.draw_error(&msg);
                 }
+            },
 
-                self.state = GameState::Round(0, self.next_turn(self.dealer), false, false, false);
+            GameState::Resolving if self.table.cash.is_some() && self.key_pressed(KeyCode::Char('o')) => {
+                let sitting_out = !self.table.players[0].sitting_out;
+
+                if let Err(msg) = self.table.set_sitting_out(0, sitting_out) {
+                    self.draw_error(&msg);
+                }
+            },
+
+            GameState::Resolving if self.table.cash.is_some() && self.key_pressed(KeyCode::Char('x')) => {
+                self.leave_table();
+            },
+
+            GameState::Resolving if self.enter_pressed() && !self.table.can_continue() => {
+                self.draw_error(&"Rebuy to keep playing, there is nobody else left");
+            },
+
+            GameState::Resolving if self.enter_pressed() => {
+                self.table.finish_hand();
+
+                if let GameState::End(_) = self.table.state {
+                    self.draw_results();
+                    self.discard_save();
+                }
+            },
+
+            GameState::End(_) if self.enter_pressed() => {
+                self.reset_table();
+            },
+
+            _ => {}
+        }
+
+        // Showdowns take every bet to the pot within the update, so they are read before it
+        let contributions = self.table.players.iter().map(Player::contribution).collect::<Vec<_>>();
+
+        // Each street of a runout is dealt once the previous one has been on the table for a while
+        if !matches!(self.table.state, GameState::Runout(..)) || self.runout_timer.done() {
+            self.table.update(&mut self.controls);
+
+            if let GameState::Runout(..) = self.table.state {
+                self.runout_timer.start();
+            }
+        }
+
+        // Hide the human options once their turn is over
+        if let GameState::Round(_, 0, true, true, _) | GameState::Straddle(0) = prev_state && self.table.state != prev_state {
+            self.clear_info();
+        }
+
+        for event in self.table.drain_events() {
+            if let Some(hand) = self.history.record(&event) && let Err(msg) = append_hand(Path::new(&self.config.history_file), &hand) {
+                self.print_msg(msg);
+            }
+
+            if self.stats.record(&event) && let Err(msg) = self.stats.write(Path::new(&self.config.stats_file)) {
+                self.print_msg(msg);
+            }
+
+            if let GameEvent::Showdown { board: 0, .. } = event {
+                self.collect_bets(&contributions);
+            }
+
+            self.handle_event(&event);
+        }
+
+        // Animations are drawn over the table as it was when they started
+        if let Some(animation) = &mut self.animation && !animation.is_started() {
+            save_screen();
+            animation.start();
+        }
+
+        false
+    }
+
+    pub fn handle_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::HandStarted { .. } => {
+                self.draw_baize();
 
-                // Draw chips
                 self.draw_player_chips();
                 self.draw_player_bets();
                 self.draw_dealer_chip();
+
+                self.deal_cards();
             },
 
-            GameState::Round(num_flipped, turn, sb, bb, mut initial) => {                
-                if !self.players[turn].actor.turn_started() {
-                    self.players[turn].actor.start_turn();
-                }
+            GameEvent::ActionRejected { seat: 0, error, .. } => {
+                // Keys stay pressed until released, so drop them to avoid repeating the same action
+                self.controls.release_all();
+                self.draw_error(error);
+            },
 
-                // Information for the actors to decide
-                let actor_info = ActorInfo {
-                    player: turn,
-                    last_raise: self.last_raise,
-                    current_bet: self.current_bet,
-                    hand: self.players[turn].hand.clone(),
-                    community: self.board[..num_flipped].to_vec(),
-                    players: self.players.iter().enumerate()
-                        .filter(|p| !p.1.lost())
-                        .map(|(i, p)| (i, (p.money, p.bet, p.folded)))
-                        .collect(),
-                };
-
-                if !sb && !bb { // Small blind
-                    if self.players[turn].actor.done(true, &mut self.controls, actor_info) {
-                        self.perform_action(Action::Raise(SMALL_BLIND), turn);
-                        self.players[turn].actor.end_turn();
-    
-                        self.state = GameState::Round(num_flipped, self.next_turn(turn), true, bb, true);
-                    }
+            GameEvent::BlindPosted { .. } => {
+                self.draw_player_chips();
+                self.draw_player_bets();
+            },
 
-                } else if sb && !bb { // Big blind
-                    if self.players[turn].actor.done(true, &mut self.controls, actor_info) {
-                        self.perform_action(Action::Raise(BIG_BLIND - SMALL_BLIND), turn);
-                        self.players[turn].actor.end_turn();
-    
-                        self.state = GameState::Round(num_flipped, self.next_turn(turn), true, true, true);
-                        self.players[0].hand.iter_mut().for_each(Card::reset_draw_cache);
-                        self.last_raise = BIG_BLIND;
-                    }                    
-
-                } else {
-                    // Normal turn
-                    let only_one_left = self.players.iter().map(|i| !i.folded && !i.lost()).count() == 1;
-
-                    if !only_one_left && !self.players[turn].folded && !self.players[turn].is_all_in() && (!initial || self.players[turn].bet < self.current_bet) {
-                        if turn == 0 {
-                            let player_money = self.players[turn].money;
-                            let call_amount = self.current_bet - self.players[turn].bet;
-                            let max_raise = player_money - call_amount.min(player_money);
-                            let min_raise = BIG_BLIND.max(self.last_raise).min(max_raise);
-
-                            let raise_bet = if initial { "Raise" } else { "Bet" };
-                            let raise_all_in = |c: usize| {
-                                if (c + call_amount) >= player_money {
-                                    "All-in".into()
-                                } else {
-                                    format!("{raise_bet} {c}")
-                                }
-                            };
-
-                            self.draw_info_at(
-                                31, 23, 
-                                vec!(
-                                    if self.players[turn].bet == self.current_bet {
-                                        format!("[C]   Check")
-                                    
-                                    } else if call_amount <= player_money {
-                                        format!("[C]   Call {}", self.current_bet)
-
-                                    } else {
-                                        format!("[C]   All-in")
-                                    },
-                                    format!("[R]   {}", raise_all_in(min_raise)),
-                                    format!("[D]   {}", raise_all_in((min_raise * 2).min(max_raise))),
-                                    format!("[T]   {}", raise_all_in((min_raise * 3).min(max_raise))),
-                                    format!("[B+D] {}", raise_all_in(self.current_bet.min(max_raise))),
-                                    format!("[B+T] {}", raise_all_in((self.current_bet * 2).min(max_raise))),
-                                    format!("[F]   Fold")
-                                )
-                            );
-                        }
-
-                        if self.players[turn].actor.done(false, &mut self.controls, actor_info) {
-                            let action = self.players[turn].actor.get_action();
-
-                            if matches!(action, Action::Raise(_)) {
-                                initial = true;
-                            }
-
-                            self.perform_action(action, turn);
-                            
-                            self.players[turn].actor.end_turn();
-
-                            if turn == 0 {
-                                self.clear_info();
-                            }
-    
-                        } else {
-                            return false; // Wait for the actor to be done
-                        }
-
-                    } else {
-                        if turn == 0 {
-                            self.clear_info();
-                        }
-
-                        // End turn inmediately if no action is possible
-                        self.players[turn].actor.end_turn();
-                    }
+            GameEvent::BombPotStarted { .. } => {
+                self.draw_board_label("Bomb pot");
+            },
 
-                    let balanced_bet = self.players.iter()
-                        .filter(|p| !p.folded)
-                        .filter(|p| !p.is_all_in())
-                        .filter(|p| !p.lost())
-                        .all(|i| i.bet == self.current_bet);
-
-                    // Pass stage
-                    if turn == self.dealer && (balanced_bet || only_one_left) {
-                        if num_flipped < 5 {
-                            // Pre-flop
-                            if num_flipped == 0 {
-                                self.board[0].reset_draw_cache();
-                                self.board[1].reset_draw_cache();
-                                self.board[2].reset_draw_cache();
-                                self.state = GameState::Round(3, self.next_turn(turn), true, true, false);
-
-                            } else {
-                                self.board[num_flipped].reset_draw_cache();
-                                self.state = GameState::Round(num_flipped + 1, self.next_turn(self.dealer), true, true, false);
-                            }
-                            
-                            self.last_raise = 0;
-
-                        } else {
-                            // Calculate winner and draw plays
-                            let plays = self.players.iter()
-                                .map(|p| analyze_play(&p.hand, &self.board))
-                                .collect();
-
-                            let valid_players = self.players.iter()
-                                .enumerate()
-                                .filter(|p| !p.1.folded && !p.1.lost())
-                                .map(|p| p.0)
-                                .collect();
-
-                            let winners = self.solve_pots(&plays);
-
-                            self.draw_player_plays(&plays, &winners, &valid_players);
-
-                            // Reset draw cache and proceed
-                            self.players.iter_mut().flat_map(|p| &mut p.hand).for_each(Card::reset_draw_cache);
-                            self.state = GameState::Resolving;
-                        }
-
-                    } else {
-                        self.state = GameState::Round(num_flipped, self.next_turn(turn), sb, bb, initial);
-                    }
-                }
+            GameEvent::ActionTaken { .. } => {
+                self.draw_player_chips();
+                self.draw_player_bets();
             },
 
-            GameState::Collecting => {
-                for p in &mut self.players {
-                    while !p.hand.is_empty() {
-                        self.deck.push(p.hand.pop().unwrap());
-                    }
-                }
+            GameEvent::RunoutStarted { .. } => {
+                self.draw_equities();
+            },
 
-                while !self.board.is_empty() {
-                    self.deck.push(self.board.pop().unwrap());
-                }
+            GameEvent::StreetDealt { board: 0, street, cards } => {
+                self.draw_equities();
+                self.flip_cards(*street, cards);
+            },
 
-                self.deck.reset_draw_cache();
-                self.deck.shuffle();
+            // The second board is drawn over the first one
+            GameEvent::StreetDealt { street, cards, .. } => {
+                self.draw_board_label("Second board");
+                self.draw_equities();
+                self.flip_cards(*street, cards);
+            },
 
-                self.state = GameState::Dealing;
+            GameEvent::PotAwarded { seat, amount, .. } => {
+                let to = self.layout.seats[*seat].chips;
+                self.animate(vec!(Sprite::Chips { amount: *amount, from: self.pot_pos(*amount), to }), 1.0);
             },
 
-            GameState::Resolving => {
-                if self.controls.is_pressed(KeyCode::Enter) && !self.controls.is_locked(KeyCode::Enter) {
-                    self.controls.lock(KeyCode::Enter, Duration::from_millis(500));
-
-                    let finished = self.players.iter().filter(|p| !p.lost()).count() == 1;
-
-                    if finished {
-                        // Take cards back to deck
-                        for p in &mut self.players {
-                            while !p.hand.is_empty() {
-                                self.deck.push(p.hand.pop().unwrap());
-                            }
-                        }
-
-                        while !self.board.is_empty() {
-                            self.deck.push(self.board.pop().unwrap());
-                        }
-        
-                        self.deck.reset_draw_cache();
-                        self.deck.shuffle();
-
-                        // State transition
-                        let won = !self.players[0].lost();
-                        self.state = GameState::End(won);
-
-                        self.draw_win_text(won);
-                        
-                    } else {
-                        self.state = GameState::Collecting;
-                        self.dealer = self.next_turn(self.dealer);
-                        self.current_bet = 0;
-                    }
+            GameEvent::Showdown { board: 0, hands } if self.table.second_board.is_some() => {
+                let best = hands.iter().map(|(_, play)| play).max();
+                let winners = hands.iter().filter(|(_, play)| Some(play) == best).map(|(seat, _)| self.table.players[*seat].name.clone()).collect::<Vec<_>>();
+
+                if let Some(best) = best {
+                    self.draw_board_label(&format!("First board: {} with {}", winners.join(" and "), best.name()));
                 }
             },
 
-            GameState::End(_) => {
-                if self.controls.is_pressed(KeyCode::Enter) && !self.controls.is_locked(KeyCode::Enter) {
-                    self.controls.lock(KeyCode::Enter, Duration::from_millis(500));
-                
-                    self.state = GameState::MainMenu(false);
+            GameEvent::Showdown { .. } => {
+                self.clear_equities();
+
+                if let Some(showdown) = &self.table.showdown {
+                    self.draw_player_plays(&showdown.plays, &showdown.winners, &showdown.contenders);
                 }
-            }
+            },
+
+            GameEvent::ChipsAdded { .. } |
+            GameEvent::PlayerJoined { .. } => {
+                self.draw_player_chips();
+                self.draw_session();
+                self.draw_cash_options();
+            },
+
+            GameEvent::SatOut { .. } |
+            GameEvent::SatIn { .. } => {
+                self.draw_session();
+                self.draw_cash_options();
+            },
+
+            GameEvent::HandFinished => {
+                self.draw_cash_options();
+            },
+
+            GameEvent::LevelStarted { .. } |
+            GameEvent::ActionRejected { .. } |
+            GameEvent::HoleCardsDealt { .. } |
+            GameEvent::UncalledBetReturned { .. } |
+            GameEvent::PlayerEliminated { .. } => {}
+        }
+    }
+
+    pub fn render_replay(&mut self) {
+        let Some(replay) = &mut self.replay else {
+            return;
         };
 
-        return false;
+        if !replay.changed {
+            return;
+        }
+
+        replay.changed = false;
+
+        let hand = replay.current_hand().clone();
+        let frame = replay.current_frame().clone();
+
+        let status = format!(
+            "Hand {}/{}  #{}  {}   {}",
+            replay.hand + 1, replay.hands.len(), hand.id, hand.game, frame.message
+        );
+
+        let controls = format!(
+            "[Left/Right] Step   [Up/Down] Street   [P/N] Hand   [Space] {}   [+/-] Speed x{}   [Q] Menu",
+            if replay.playing { "Pause" } else { "Play" }, replay.speed_factor()
+        );
+
+        // Tables of a different size need their own layout
+        if self.table.players.len() != hand.names.len() {
+            let players = hand.names.iter()
+                .map(|name| Player::new(name.clone(), 0, Box::new(SimpleActor::new())))
+                .collect();
+
+            self.table = Table::new(players, 0, 0, None);
+            self.layout = Layout::new(hand.names.len(), self.layout.rows, self.layout.cols);
+        }
+
+        for (seat, player) in self.table.players.iter_mut().enumerate() {
+            player.name = hand.names[seat].clone();
+            player.money = frame.stacks[seat];
+            player.bet = frame.bets[seat];
+            player.ante = 0;
+            player.folded = frame.folded[seat];
+            player.hand = hand.hands[seat].clone();
+        }
+
+        // Cards that were never dealt are drawn face down
+        self.table.dealer = hand.dealer;
+        self.table.board = if frame.board == 0 { hand.board.clone() } else { hand.second_board.clone() };
+
+        while self.table.board.len() < self.layout.board.len() {
+            self.table.board.push(Card::new(1, Suit::Spades));
+        }
+
+        self.draw_baize();
+        self.draw_player_chips();
+        self.draw_player_bets();
+        self.draw_dealer_chip();
+
+        if frame.board == 1 {
+            self.draw_board_label("Second board");
+        }
+
+        if let Some(turn) = frame.turn {
+            self.draw_turn_chip(turn);
+        }
+
+        for (i, (card, pos)) in self.table.board.iter().zip(&self.layout.board).enumerate() {
+            card.draw(&self.theme, pos.col, pos.row, i >= frame.flipped);
+        }
+
+        for (player, seat) in self.table.players.iter().zip(&self.layout.seats) {
+            for (card, pos) in player.hand.iter().zip(&seat.cards) {
+                card.draw(&self.theme, pos.col, pos.row, false);
+            }
+        }
+
+        let width = status.len().max(controls.len());
+        let info = self.layout.info;
+
+        self.draw_info_at(info.row, info.col.saturating_sub(width / 2 + 1), vec!(status, controls));
     }
 
     pub fn render(&mut self) {
-        match self.state {
+        if self.layout.too_small() {
+            return;
+        }
+
+        if self.replay.is_some() {
+            return self.render_replay();
+        }
+
+        if self.stats_screen == Some(false) {
+            self.draw_stats();
+            self.stats_screen = Some(true);
+            return;
+        }
+
+        if let Some(animation) = &self.animation {
+            return self.draw_animation(animation);
+        }
+
+        match self.table.state {
             GameState::MainMenu(drawn) => {
                 if !drawn {
                     // Draw green baize
@@ -766,8 +1386,9 @@ impl Game {
                     // Draw text
                     self.draw_start_text();
                     self.draw_logo();
+                    self.draw_menu_options();
 
-                    self.state = GameState::MainMenu(true);
+                    self.table.state = GameState::MainMenu(true);
                 }
             },
 
@@ -776,48 +1397,49 @@ impl Game {
             GameState::End(_) => {},
             
             GameState::Round(num_flipped, turn, sb, bb, _) => {
-                self.draw_turn_chip(turn);
+                self.draw_round(num_flipped, turn, sb && bb);
+            },
 
-                // Center cards
-                for (i, card) in self.board.iter_mut().enumerate() {
-                    card.draw(27 + i * 15, 16, i >= num_flipped);
-                }
+            GameState::Straddle(turn) => {
+                self.draw_round(0, turn, true);
+            },
 
-                // Players
-                for (i, card) in self.players[3].hand.iter_mut().enumerate() {
-                    card.draw(109, 11 + i * 10, true);
-                }
+            GameState::Runout(num_flipped, board) => {
+                self.draw_level_clock();
+                self.draw_session();
 
-                for (i, card) in self.players[2].hand.iter_mut().enumerate() {
-                    card.draw(25 + 24 + i * 16, 2, true);
-                }
+                let cards = if board == 0 { &self.table.board } else { self.table.second_board.as_ref().unwrap_or(&self.table.board) };
 
-                for (i, card) in self.players[1].hand.iter_mut().enumerate() {
-                    card.draw(5, 11 + i * 10, true);
+                for (i, (card, pos)) in cards.iter().zip(&self.layout.board).enumerate() {
+                    card.draw(&self.theme, pos.col, pos.row, i >= num_flipped);
                 }
 
-                for (i, card) in self.players[0].hand.iter_mut().enumerate() {
-                    card.draw(25 + 24 + i * 16, 30, !sb || !bb);
+                // Live hands are played face up
+                for (i, (player, seat)) in self.table.players.iter().zip(&self.layout.seats).enumerate() {
+                    let back = i != 0 && player.folded;
+
+                    for (card, pos) in player.hand.iter().zip(&seat.cards) {
+                        card.draw(&self.theme, pos.col, pos.row, back);
+                    }
                 }
             },
 
             GameState::Resolving => {
-                let visibility = self.players.iter().map(|i| i.folded).collect::<Vec<_>>();
+                self.draw_level_clock();
+                self.draw_session();
 
-                for (i, card) in self.players[3].hand.iter_mut().enumerate() {
-                    card.draw(109, 11 + i * 10, visibility[3]);
-                }
+                let board = self.table.second_board.as_ref().unwrap_or(&self.table.board);
 
-                for (i, card) in self.players[2].hand.iter_mut().enumerate() {
-                    card.draw(25 + 24 + i * 16, 2, visibility[2]);
+                for (card, pos) in board.iter().zip(&self.layout.board) {
+                    card.draw(&self.theme, pos.col, pos.row, false);
                 }
 
-                for (i, card) in self.players[1].hand.iter_mut().enumerate() {
-                    card.draw(5, 11 + i * 10, visibility[1]);
-                }
+                for (i, (player, seat)) in self.table.players.iter().zip(&self.layout.seats).enumerate() {
+                    let back = i != 0 && player.folded;
 
-                for (i, card) in self.players[0].hand.iter_mut().enumerate() {
-                    card.draw(25 + 24 + i * 16, 30, false);
+                    for (card, pos) in player.hand.iter().zip(&seat.cards) {
+                        card.draw(&self.theme, pos.col, pos.row, back);
+                    }
                 }
             },
         }
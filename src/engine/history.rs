@@ -0,0 +1,619 @@
+use std::{fs::OpenOptions, io::Write, path::Path, time::{SystemTime, UNIX_EPOCH}};
+
+use crate::{actor::action::Action, engine::event::{Blind, GameEvent, Street}, poker::{card::Card, play::Play}};
+
+pub const DEFAULT_HISTORY_PATH: &str = "console-ace-history.txt";
+
+pub const TABLE_NAME: &str = "Console Ace";
+
+// Builds PokerStars hand histories from the events of the table, one hand at a time
+pub struct HandHistory {
+    names: Vec<String>,
+    next_id: u64,
+    started: bool,
+    lines: Vec<String>,
+    dealer: usize,
+    blinds: Vec<Option<Blind>>,
+    stacks: Vec<usize>,
+    street_bets: Vec<usize>,
+    street: Street,
+    board: Vec<Card>,
+    second_board: Vec<Card>,
+    hands: Vec<Vec<Card>>,
+    hole_cards_written: bool,
+    folded: Vec<Option<Street>>,
+    shown: Vec<Vec<(usize, Play)>>, // Hands shown on each board
+    uncalled: Vec<(usize, usize)>,
+    pots: Vec<(usize, usize, usize, usize)> // (Board, pot, seat, amount)
+}
+
+fn cards_to_str(cards: &[Card]) -> String {
+    format!("[{}]", cards.iter().map(Card::short_name).collect::<Vec<_>>().join(" "))
+}
+
+// Date and time in UTC from a UNIX timestamp
+fn format_time(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let (hour, minute, second) = (secs / 3600 % 24, secs / 60 % 60, secs % 60);
+
+    // Civil date from days since the epoch
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!("{year}/{month:02}/{day:02} {hour}:{minute:02}:{second:02} UTC")
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn pot_name(pot: usize, num_pots: usize) -> String {
+    match pot {
+        _ if num_pots == 1 => "pot".into(),
+        0 => "main pot".into(),
+        n => format!("side pot-{n}")
+    }
+}
+
+impl HandHistory {
+    pub fn new(names: Vec<String>) -> Self {
+        let num_seats = names.len();
+
+        HandHistory {
+            names,
+            next_id: now() * 100, // Keeps hand numbers unique between sessions
+            started: false,
+            lines: vec!(),
+            dealer: 0,
+            blinds: vec![None; num_seats],
+            stacks: vec![0; num_seats],
+            street_bets: vec![0; num_seats],
+            street: Street::PreFlop,
+            board: vec!(),
+            second_board: vec!(),
+            hands: vec![vec!(); num_seats],
+            hole_cards_written: false,
+            folded: vec![None; num_seats],
+            shown: vec!(),
+            uncalled: vec!(),
+            pots: vec!()
+        }
+    }
+
+    fn seats_in_hand(&self) -> Vec<usize> {
+        (0..self.names.len()).filter(|i| !self.hands[*i].is_empty()).collect()
+    }
+
+    // The hole cards section goes after the blinds, which are posted once the cards are dealt
+    fn write_hole_cards(&mut self) {
+        if self.hole_cards_written {
+            return;
+        }
+
+        self.hole_cards_written = true;
+        self.lines.push("*** HOLE CARDS ***".into());
+
+        for seat in self.seats_in_hand() {
+            self.lines.push(format!("Dealt to {} {}", self.names[seat], cards_to_str(&self.hands[seat])));
+        }
+    }
+
+    fn action_line(&mut self, seat: usize, amount: usize) -> String {
+        let facing = self.street_bets.iter().copied().max().unwrap_or(0);
+
+        self.street_bets[seat] += amount;
+        self.stacks[seat] -= amount;
+
+        let total = self.street_bets[seat];
+
+        let line = if amount == 0 {
+            "checks".to_string()
+        } else if total <= facing {
+            format!("calls {amount}")
+        } else if facing == 0 {
+            format!("bets {amount}")
+        } else {
+            format!("raises {} to {total}", total - facing)
+        };
+
+        if amount > 0 && self.stacks[seat] == 0 {
+            format!("{line} and is all-in")
+        } else {
+            line
+        }
+    }
+
+    // Records an event, returning the text of the hand once it is finished
+    pub fn record(&mut self, event: &GameEvent) -> Option<String> {
+        // A hand resumed from a save was started before, so it is left out, seats still change between hands
+        if !self.started && !matches!(event, GameEvent::HandStarted { .. } | GameEvent::PlayerJoined { .. }) {
+            return None;
+        }
+
+        match event {
+            GameEvent::HandStarted { dealer, small_blind, big_blind, stacks } => {
+                *self = HandHistory { next_id: self.next_id + 1, ..HandHistory::new(std::mem::take(&mut self.names)) };
+
+                self.started = true;
+                self.dealer = *dealer;
+                self.stacks = stacks.clone();
+
+                self.lines.push(format!("PokerStars Hand #{}: Hold'em No Limit ({small_blind}/{big_blind}) - {}", self.next_id, format_time(now())));
+                self.lines.push(format!("Table '{TABLE_NAME}' {}-max Seat #{} is the button", self.names.len(), dealer + 1));
+
+                for (seat, stack) in stacks.iter().enumerate().filter(|(_, s)| **s > 0) {
+                    self.lines.push(format!("Seat {}: {} ({stack} in chips)", seat + 1, self.names[seat]));
+                }
+            },
+
+            GameEvent::HoleCardsDealt { seat, cards } => {
+                self.hands[*seat] = cards.clone();
+            },
+
+            GameEvent::BlindPosted { seat, blind, amount } => {
+                let name = &self.names[*seat];
+
+                let line = match blind {
+                    Blind::Ante => format!("{name}: posts the ante {amount}"),
+                    Blind::Small => format!("{name}: posts small blind {amount}"),
+                    Blind::Big => format!("{name}: posts big blind {amount}"),
+                    Blind::Straddle => format!("{name}: posts straddle {amount}")
+                };
+
+                if *blind == Blind::Ante {
+                    self.stacks[*seat] -= amount;
+                } else {
+                    self.blinds[*seat] = Some(*blind);
+                    self.street_bets[*seat] += amount;
+                    self.stacks[*seat] -= amount;
+                }
+
+                self.lines.push(line);
+            },
+
+            GameEvent::ActionTaken { seat, action, amount } => {
+                self.write_hole_cards();
+
+                let line = if *action == Action::Fold {
+                    self.folded[*seat] = Some(self.street);
+                    "folds".to_string()
+                } else {
+                    self.action_line(*seat, *amount)
+                };
+
+                self.lines.push(format!("{}: {line}", self.names[*seat]));
+            },
+
+            GameEvent::StreetDealt { board: 1, street, cards } => {
+                // The second board starts with the cards both share
+                if self.second_board.is_empty() {
+                    self.second_board = self.board[..street.num_flipped() - cards.len()].to_vec();
+                }
+
+                let header = match street {
+                    Street::Flop => format!("*** SECOND FLOP *** {}", cards_to_str(cards)),
+                    _ => format!("*** SECOND {} *** {} {}", street.name().to_uppercase(), cards_to_str(&self.second_board), cards_to_str(cards))
+                };
+
+                self.second_board.extend(cards.iter().cloned());
+                self.lines.push(header);
+            },
+
+            GameEvent::StreetDealt { street, cards, .. } => {
+                self.write_hole_cards();
+                self.street = *street;
+                self.street_bets.iter_mut().for_each(|b| *b = 0);
+
+                let header = match street {
+                    Street::Flop => format!("*** FLOP *** {}", cards_to_str(cards)),
+                    _ => format!("*** {} *** {} {}", street.name().to_uppercase(), cards_to_str(&self.board), cards_to_str(cards))
+                };
+
+                self.board.extend(cards.iter().cloned());
+                self.lines.push(header);
+            },
+
+            GameEvent::Showdown { hands, .. } => {
+                self.shown.push(hands.clone());
+            },
+
+            GameEvent::UncalledBetReturned { seat, amount } => {
+                self.uncalled.push((*seat, *amount));
+            },
+
+            GameEvent::PotAwarded { pot, seat, amount } => {
+                // A pot can be paid in several layers, for the board that was just shown
+                let board = self.shown.len().saturating_sub(1);
+
+                match self.pots.iter_mut().find(|p| p.0 == board && p.1 == *pot && p.2 == *seat) {
+                    Some(won) => won.3 += amount,
+                    None => self.pots.push((board, *pot, *seat, *amount))
+                }
+            },
+
+            GameEvent::PlayerJoined { seat, name } => {
+                self.names[*seat] = name.clone();
+            },
+
+            GameEvent::HandFinished => {
+                self.started = false;
+                return Some(self.finish());
+            },
+
+            GameEvent::LevelStarted { .. } |
+            GameEvent::BombPotStarted { .. } |
+            GameEvent::ActionRejected { .. } |
+            GameEvent::RunoutStarted { .. } |
+            GameEvent::PlayerEliminated { .. } |
+            GameEvent::ChipsAdded { .. } |
+            GameEvent::SatOut { .. } |
+            GameEvent::SatIn { .. } => {}
+        }
+
+        None
+    }
+
+    fn finish(&mut self) -> String {
+        self.write_hole_cards();
+
+        for (seat, amount) in std::mem::take(&mut self.uncalled) {
+            self.lines.push(format!("Uncalled bet ({amount}) returned to {}", self.names[seat]));
+        }
+
+        let showdown = self.shown.first().is_some_and(|hands| hands.len() > 1);
+        let num_pots = self.pots.iter().map(|p| p.1 + 1).max().unwrap_or(0);
+
+        // Hands run twice have a show down for each board
+        let headers = match self.shown.len() {
+            2 => vec!("*** FIRST SHOW DOWN ***", "*** SECOND SHOW DOWN ***"),
+            _ => vec!("*** SHOW DOWN ***")
+        };
+
+        for (board, header) in headers.into_iter().enumerate() {
+            if showdown {
+                self.lines.push(header.into());
+
+                for (seat, play) in &self.shown[board] {
+                    self.lines.push(format!("{}: shows {} ({})", self.names[*seat], cards_to_str(&self.hands[*seat]), play.name()));
+                }
+            }
+
+            // Side pots are collected before the main pot
+            for (_, pot, seat, amount) in self.pots.iter().rev().filter(|p| p.0 == board) {
+                self.lines.push(format!("{} collected {amount} from {}", self.names[*seat], pot_name(*pot, num_pots)));
+            }
+        }
+
+        // Summary
+        let pot_totals = (0..num_pots)
+            .map(|pot| self.pots.iter().filter(|p| p.1 == pot).map(|p| p.3).sum::<usize>())
+            .collect::<Vec<_>>();
+
+        let total = pot_totals.iter().sum::<usize>();
+
+        self.lines.push("*** SUMMARY ***".into());
+
+        if num_pots > 1 {
+            let pots = pot_totals.iter().enumerate()
+                .map(|(pot, amount)| format!("{} {amount}.", pot_name(pot, num_pots)))
+                .map(|s| s[..1].to_uppercase() + &s[1..])
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            self.lines.push(format!("Total pot {total} {pots} | Rake 0"));
+
+        } else {
+            self.lines.push(format!("Total pot {total} | Rake 0"));
+        }
+
+        if !self.second_board.is_empty() {
+            self.lines.push(format!("FIRST Board {}", cards_to_str(&self.board)));
+            self.lines.push(format!("SECOND Board {}", cards_to_str(&self.second_board)));
+
+        } else if !self.board.is_empty() {
+            self.lines.push(format!("Board {}", cards_to_str(&self.board)));
+        }
+
+        for seat in self.seats_in_hand() {
+            let role = match self.blinds[seat] {
+                _ if seat == self.dealer => " (button)",
+                Some(Blind::Small) => " (small blind)",
+                Some(Blind::Big) => " (big blind)",
+                _ => ""
+            };
+
+            let won = self.pots.iter().filter(|p| p.2 == seat).map(|p| p.3).sum::<usize>();
+
+            // What the hand did on each board
+            let boards = self.shown.iter().enumerate()
+                .filter_map(|(board, hands)| {
+                    let (_, play) = hands.iter().find(|(s, _)| *s == seat)?;
+                    let won = self.pots.iter().filter(|p| p.0 == board && p.2 == seat).map(|p| p.3).sum::<usize>();
+
+                    Some(if won > 0 { format!("won ({won}) with {}", play.name()) } else { format!("lost with {}", play.name()) })
+                })
+                .collect::<Vec<_>>();
+
+            let result = match self.folded[seat] {
+                Some(Street::PreFlop) => "folded before Flop".to_string(),
+                Some(street) => format!("folded on the {}", street.name()),
+                None if showdown && !boards.is_empty() => format!("showed {} and {}", cards_to_str(&self.hands[seat]), boards.join(", and ")),
+                None if won > 0 => format!("collected ({won})"),
+                None => "mucked".to_string()
+            };
+
+            self.lines.push(format!("Seat {}: {}{role} {result}", seat + 1, self.names[seat]));
+        }
+
+        self.lines.join("\n")
+    }
+}
+
+pub fn append_hand(path: &Path, hand: &str) -> Result<(), String> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)
+        .map_err(|e| format!("Unable to open {}: {e}", path.display()))?;
+
+    // Hands are separated by blank lines like in the PokerStars files
+    write!(file, "{hand}\n\n\n").map_err(|e| format!("Unable to write {}: {e}", path.display()))
+}
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::poker::play::analyze_play;
+
+    const NAMES: [&str; 4] = ["Alice", "Bob", "Carol", "Dave"];
+
+    fn cards(names: &str) -> Vec<Card> {
+        names.split_whitespace().map(|name| Card::from_short_name(name).unwrap()).collect()
+    }
+
+    // Events of the start of a hand with the button on the first seat
+    fn started(hands: &[&str]) -> Vec<GameEvent> {
+        let mut events = vec!(GameEvent::HandStarted { dealer: 0, small_blind: 5, big_blind: 10, stacks: vec![1000; hands.len()] });
+
+        events.extend(hands.iter().enumerate().map(|(seat, hand)| GameEvent::HoleCardsDealt { seat, cards: cards(hand) }));
+        events
+    }
+
+    fn blind(seat: usize, blind: Blind, amount: usize) -> GameEvent {
+        GameEvent::BlindPosted { seat, blind, amount }
+    }
+
+    fn action(seat: usize, action: Action, amount: usize) -> GameEvent {
+        GameEvent::ActionTaken { seat, action, amount }
+    }
+
+    fn streets(board: usize, cards_dealt: &str) -> Vec<GameEvent> {
+        let all = cards(cards_dealt);
+
+        [(Street::Flop, 0..3), (Street::Turn, 3..4), (Street::River, 4..5)].into_iter()
+            .map(|(street, range)| GameEvent::StreetDealt { board, street, cards: all[range].to_vec() })
+            .collect()
+    }
+
+    fn showdown(board: usize, seats: &[(usize, &str)], community: &str) -> GameEvent {
+        let hands = seats.iter().map(|(seat, hand)| (*seat, analyze_play(&cards(hand), &cards(community)))).collect();
+
+        GameEvent::Showdown { board, hands }
+    }
+
+    fn write(events: Vec<GameEvent>) -> String {
+        let num_seats = events.iter().find_map(|e| match e {
+            GameEvent::HandStarted { stacks, .. } => Some(stacks.len()),
+            _ => None
+        }).unwrap();
+
+        let mut history = HandHistory::new(NAMES[..num_seats].iter().map(|n| n.to_string()).collect());
+
+        events.iter().find_map(|e| history.record(e)).expect("The hand did not finish")
+    }
+
+    // Heads-up all-in before the flop, where each board goes to a different player
+    pub(crate) fn run_twice_hand() -> String {
+        let mut events = started(&["Ah As", "Kh Ks"]);
+
+        events.extend([
+            blind(0, Blind::Small, 5),
+            blind(1, Blind::Big, 10),
+            action(0, Action::AllIn, 995),
+            action(1, Action::Call, 990)
+        ]);
+
+        events.extend(streets(0, "2c 7d 9h Jc 4s"));
+        events.extend(streets(1, "Kd 2h 7c 9s 4d"));
+
+        events.extend([
+            showdown(0, &[(0, "Ah As"), (1, "Kh Ks")], "2c 7d 9h Jc 4s"),
+            GameEvent::PotAwarded { pot: 0, seat: 0, amount: 1000 },
+            showdown(1, &[(0, "Ah As"), (1, "Kh Ks")], "Kd 2h 7c 9s 4d"),
+            GameEvent::PotAwarded { pot: 0, seat: 1, amount: 1000 },
+            GameEvent::HandFinished
+        ]);
+
+        write(events)
+    }
+
+    // Raised pre-flop, called and checked down, the big blind wins at showdown
+    pub(crate) fn showdown_hand() -> String {
+        let mut events = started(&["Ah Kd", "7c 2d", "Qh Qs"]);
+
+        events.extend([
+            blind(1, Blind::Small, 5),
+            blind(2, Blind::Big, 10),
+            action(0, Action::RaiseTo(30), 30),
+            action(1, Action::Fold, 0),
+            action(2, Action::Call, 20)
+        ]);
+
+        for street in streets(0, "2c 7d 9h Jc 4s") {
+            events.extend([street, action(2, Action::Check, 0), action(0, Action::Check, 0)]);
+        }
+
+        events.extend([
+            showdown(0, &[(0, "Ah Kd"), (2, "Qh Qs")], "2c 7d 9h Jc 4s"),
+            GameEvent::PotAwarded { pot: 0, seat: 2, amount: 65 },
+            GameEvent::HandFinished
+        ]);
+
+        write(events)
+    }
+
+    fn assert_lines(text: &str, expected: &[&str]) {
+        let lines = text.lines().collect::<Vec<_>>();
+        let mut from = 0;
+
+        // In this order, with anything in between
+        for line in expected {
+            match lines[from..].iter().position(|l| l == line) {
+                Some(i) => from += i + 1,
+                None => panic!("Missing line {line:?} in order in:\n{text}")
+            }
+        }
+    }
+
+    #[test]
+    fn hand_going_to_showdown() {
+        assert_lines(&showdown_hand(), &[
+            "Table 'Console Ace' 3-max Seat #1 is the button",
+            "Seat 1: Alice (1000 in chips)",
+            "Bob: posts small blind 5",
+            "Carol: posts big blind 10",
+            "*** HOLE CARDS ***",
+            "Dealt to Alice [Ah Kd]",
+            "Alice: raises 20 to 30",
+            "Bob: folds",
+            "Carol: calls 20",
+            "*** FLOP *** [2c 7d 9h]",
+            "*** TURN *** [2c 7d 9h] [Jc]",
+            "*** RIVER *** [2c 7d 9h Jc] [4s]",
+            "*** SHOW DOWN ***",
+            "Alice: shows [Ah Kd] (High card Ace)",
+            "Carol: shows [Qh Qs] (Pair of Queens)",
+            "Carol collected 65 from pot",
+            "*** SUMMARY ***",
+            "Total pot 65 | Rake 0",
+            "Board [2c 7d 9h Jc 4s]",
+            "Seat 1: Alice (button) showed [Ah Kd] and lost with High card Ace",
+            "Seat 2: Bob (small blind) folded before Flop",
+            "Seat 3: Carol (big blind) showed [Qh Qs] and won (65) with Pair of Queens"
+        ]);
+    }
+
+    #[test]
+    fn side_pots_are_collected_before_the_main_pot() {
+        let mut events = started(&["Ah As", "Kh Ks", "Qh Qs"]);
+        events[0] = GameEvent::HandStarted { dealer: 0, small_blind: 5, big_blind: 10, stacks: vec!(1000, 100, 300) };
+
+        events.extend([
+            blind(1, Blind::Small, 5),
+            blind(2, Blind::Big, 10),
+            action(0, Action::AllIn, 1000),
+            action(1, Action::AllIn, 95),
+            action(2, Action::AllIn, 290)
+        ]);
+
+        events.extend(streets(0, "2c 7d 9h Jc 4s"));
+
+        events.extend([
+            GameEvent::UncalledBetReturned { seat: 0, amount: 700 },
+            showdown(0, &[(0, "Ah As"), (1, "Kh Ks"), (2, "Qh Qs")], "2c 7d 9h Jc 4s"),
+            GameEvent::PotAwarded { pot: 0, seat: 0, amount: 300 },
+            GameEvent::PotAwarded { pot: 1, seat: 0, amount: 400 },
+            GameEvent::HandFinished
+        ]);
+
+        assert_lines(&write(events), &[
+            "Alice: raises 990 to 1000 and is all-in",
+            "Bob: calls 95 and is all-in",
+            "Carol: calls 290 and is all-in",
+            "Uncalled bet (700) returned to Alice",
+            "*** SHOW DOWN ***",
+            "Alice collected 400 from side pot-1",
+            "Alice collected 300 from main pot",
+            "Total pot 700 Main pot 300. Side pot-1 400. | Rake 0",
+            "Seat 1: Alice (button) showed [Ah As] and won (700) with Pair of Aces",
+            "Seat 2: Bob (small blind) showed [Kh Ks] and lost with Pair of Kings"
+        ]);
+    }
+
+    #[test]
+    fn straddle_is_posted_as_a_blind_and_acts_last() {
+        let mut events = started(&["Ah Kd", "7c 2d", "8h 3s", "Qh Qs"]);
+
+        events.extend([
+            blind(1, Blind::Small, 5),
+            blind(2, Blind::Big, 10),
+            blind(3, Blind::Straddle, 20),
+            action(0, Action::Call, 20),
+            action(1, Action::Fold, 0),
+            action(2, Action::Fold, 0),
+            action(3, Action::Check, 0)
+        ]);
+
+        events.extend([
+            streets(0, "2c 7d 9h Jc 4s").remove(0),
+            action(3, Action::Bet(40), 40),
+            action(0, Action::Fold, 0),
+            GameEvent::UncalledBetReturned { seat: 3, amount: 40 },
+            showdown(0, &[(3, "Qh Qs")], "2c 7d 9h"),
+            GameEvent::PotAwarded { pot: 0, seat: 3, amount: 55 },
+            GameEvent::HandFinished
+        ]);
+
+        let text = write(events);
+
+        assert_lines(&text, &[
+            "Dave: posts straddle 20",
+            "*** HOLE CARDS ***",
+            "Alice: calls 20",
+            "Dave: checks",
+            "*** FLOP *** [2c 7d 9h]",
+            "Dave: bets 40",
+            "Alice: folds",
+            "Uncalled bet (40) returned to Dave",
+            "Dave collected 55 from pot",
+            "Total pot 55 | Rake 0",
+            "Board [2c 7d 9h]",
+            "Seat 1: Alice (button) folded on the Flop",
+            "Seat 4: Dave collected (55)"
+        ]);
+
+        assert!(!text.contains("SHOW DOWN"));
+    }
+
+    #[test]
+    fn run_twice_hand_has_a_show_down_for_each_board() {
+        let text = run_twice_hand();
+
+        assert_lines(&text, &[
+            "Alice: raises 990 to 1000 and is all-in",
+            "Bob: calls 990 and is all-in",
+            "*** FLOP *** [2c 7d 9h]",
+            "*** RIVER *** [2c 7d 9h Jc] [4s]",
+            "*** SECOND FLOP *** [Kd 2h 7c]",
+            "*** SECOND RIVER *** [Kd 2h 7c 9s] [4d]",
+            "*** FIRST SHOW DOWN ***",
+            "Alice: shows [Ah As] (Pair of Aces)",
+            "Alice collected 1000 from pot",
+            "*** SECOND SHOW DOWN ***",
+            "Bob: shows [Kh Ks] (Three Kings)",
+            "Bob collected 1000 from pot",
+            "Total pot 2000 | Rake 0",
+            "FIRST Board [2c 7d 9h Jc 4s]",
+            "SECOND Board [Kd 2h 7c 9s 4d]",
+            "Seat 1: Alice (button) showed [Ah As] and won (1000) with Pair of Aces, and lost with Pair of Aces",
+            "Seat 2: Bob (big blind) showed [Kh Ks] and lost with Pair of Kings, and won (1000) with Three Kings"
+        ]);
+
+        assert!(!text.contains("*** SHOW DOWN ***"));
+    }
+}
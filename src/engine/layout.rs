@@ -0,0 +1,242 @@
+// Size of the full layout, smaller terminals get the compact one down to the minimum size
+pub const ROWS: usize = 41;
+pub const COLS: usize = 125;
+pub const MIN_ROWS: usize = 37;
+pub const MIN_COLS: usize = 113;
+
+pub const CARD_WIDTH: usize = 11;
+pub const CARD_HEIGHT: usize = 9;
+
+const LABEL_WIDTH: usize = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Bottom, Left, Top, Right
+}
+
+#[derive(Clone, Debug)]
+pub struct SeatLayout {
+    pub side: Side,
+    pub cards: Vec<Pos>,
+    pub chips: Pos,
+    pub bet: Pos,
+    pub dealer: Pos,
+    pub turn: Pos,
+    pub play: Pos
+}
+
+#[derive(Clone, Debug)]
+pub struct Layout {
+    pub rows: usize, // Size of the terminal
+    pub cols: usize,
+    pub origin: Pos, // Top left corner of the table, which is centered in the terminal
+    pub width: usize,
+    pub compact: bool,
+    pub seats: Vec<SeatLayout>,
+    pub board: Vec<Pos>,
+    pub board_label: Pos,
+    pub info: Pos,
+    pub level: Pos
+}
+
+const fn pos(row: usize, col: usize) -> Pos {
+    Pos { row, col }
+}
+
+// Seats per side for each table size: (bottom left, left, top, right, bottom right)
+fn distribution(num_seats: usize) -> (usize, usize, usize, usize, usize) {
+    match num_seats {
+        2 => (0, 0, 1, 0, 0),
+        3 => (0, 1, 0, 1, 0),
+        4 => (0, 1, 1, 1, 0),
+        5 => (0, 1, 2, 1, 0),
+        6 => (0, 1, 3, 1, 0),
+        7 => (1, 1, 2, 1, 1),
+        8 => (1, 1, 3, 1, 1),
+        9 => (1, 1, 4, 1, 1),
+        10 => (2, 1, 4, 1, 1),
+        n => panic!("Unsupported number of seats: {n}")
+    }
+}
+
+// Seat whose hole cards lay side by side with the labels at their right, overlapping when the row is crowded
+fn horizontal_seat(side: Side, row: usize, center: usize, per_row: usize, cols: usize) -> SeatLayout {
+    let card_offset = (cols / per_row - CARD_WIDTH - 1 - LABEL_WIDTH).min(CARD_WIDTH + 1);
+    let width = card_offset + CARD_WIDTH + 1 + LABEL_WIDTH;
+
+    let col = center - width / 2;
+    let labels = col + card_offset + CARD_WIDTH + 1;
+    let cards_center = col + (card_offset + CARD_WIDTH) / 2;
+
+    let play = match side {
+        Side::Top => pos(row + CARD_HEIGHT + 1, cards_center),
+        _ => pos(row - 2, cards_center)
+    };
+
+    SeatLayout {
+        side,
+        cards: vec!(pos(row, col), pos(row, col + card_offset)),
+        chips: pos(row + 1, labels),
+        bet: pos(row + 4, labels),
+        dealer: pos(row + 7, labels),
+        turn: pos(row + 7, labels + 4),
+        play
+    }
+}
+
+// Seat whose hole cards are fanned vertically with the labels facing the board
+fn vertical_seat(side: Side, cols: usize, compact: bool) -> SeatLayout {
+    const ROW: usize = 13;
+    const CARD_OFFSET: usize = 3;
+
+    let (col, labels) = match side {
+        Side::Left => (2, 2 + CARD_WIDTH + 1),
+        _ => (cols - 2 - CARD_WIDTH, cols - 3 - CARD_WIDTH - LABEL_WIDTH)
+    };
+
+    // The row under the cards is the one of the bottom seats' labels in the compact layout,
+    // so the label goes under the others, from the inner edge of the labels over the cards
+    let play = match (side, compact) {
+        (_, true) => pos(ROW + CARD_HEIGHT + CARD_OFFSET - 2, if side == Side::Left { labels + LABEL_WIDTH } else { labels }),
+        (Side::Left, false) => pos(ROW + CARD_HEIGHT + CARD_OFFSET + 1, 2),
+        _ => pos(ROW + CARD_HEIGHT + CARD_OFFSET + 1, cols - 2)
+    };
+
+    SeatLayout {
+        side,
+        cards: vec!(pos(ROW, col), pos(ROW + CARD_OFFSET, col)),
+        chips: pos(ROW + 1, labels),
+        bet: pos(ROW + 4, labels),
+        dealer: pos(ROW + 7, labels),
+        turn: pos(ROW + 7, labels + 4),
+        play
+    }
+}
+
+fn row_centers(count: usize, cols: usize) -> Vec<usize> {
+    (0..count).map(|i| cols * (2 * i + 1) / (2 * count)).collect()
+}
+
+impl Pos {
+    fn offset(&mut self, origin: Pos) {
+        self.row += origin.row;
+        self.col += origin.col;
+    }
+}
+
+impl SeatLayout {
+    fn offset(&mut self, origin: Pos) {
+        self.cards.iter_mut().for_each(|c| c.offset(origin));
+
+        for pos in [&mut self.chips, &mut self.bet, &mut self.dealer, &mut self.turn, &mut self.play] {
+            pos.offset(origin);
+        }
+    }
+}
+
+impl Layout {
+    // Terminals smaller than the minimum get a layout of the minimum size, which is never drawn
+    pub fn new(num_seats: usize, rows: usize, cols: usize) -> Self {
+        let (bottom_left, left, top, right, bottom_right) = distribution(num_seats);
+
+        // The compact layout drops the band of the board label and the empty bottom row
+        let compact = rows < ROWS;
+        let height = if compact { MIN_ROWS } else { ROWS };
+        let width = cols.clamp(MIN_COLS, COLS);
+
+        const TOP_ROW: usize = 1;
+        let board_row = if compact { 13 } else { 16 };
+        let info_row = board_row + CARD_HEIGHT + 1;
+        let bottom_row = info_row + 5;
+
+        let bottom = bottom_left + 1 + bottom_right;
+        let bottom_centers = row_centers(bottom, width);
+        let top_centers = row_centers(top, width);
+
+        // Seats are placed clockwise, starting from the human at the bottom
+        let mut seats = vec!(horizontal_seat(Side::Bottom, bottom_row, bottom_centers[bottom_left], bottom, width));
+
+        for i in (0..bottom_left).rev() {
+            seats.push(horizontal_seat(Side::Bottom, bottom_row, bottom_centers[i], bottom, width));
+        }
+
+        if left > 0 {
+            seats.push(vertical_seat(Side::Left, width, compact));
+        }
+
+        for center in top_centers {
+            seats.push(horizontal_seat(Side::Top, TOP_ROW, center, top, width));
+        }
+
+        if right > 0 {
+            seats.push(vertical_seat(Side::Right, width, compact));
+        }
+
+        for i in (bottom_left + 1..bottom).rev() {
+            seats.push(horizontal_seat(Side::Bottom, bottom_row, bottom_centers[i], bottom, width));
+        }
+
+        // The board spreads out between the side seats
+        const SIDE_WIDTH: usize = 27;
+        let spacing = (width - 2 * SIDE_WIDTH - CARD_WIDTH) / 4;
+        let board_col = (width - 4 * spacing - CARD_WIDTH) / 2;
+
+        let mut layout = Layout {
+            rows,
+            cols,
+            origin: pos(rows.saturating_sub(height) / 2, cols.saturating_sub(width) / 2),
+            width,
+            compact,
+            seats,
+            board: (0..5).map(|i| pos(board_row, board_col + i * spacing)).collect(),
+            board_label: if compact { pos(0, width - 1) } else { pos(board_row - 2, width / 2) },
+            info: pos(info_row, width / 2),
+            level: pos(0, 1)
+        };
+
+        let origin = layout.origin;
+        layout.seats.iter_mut().for_each(|s| s.offset(origin));
+        layout.board.iter_mut().for_each(|b| b.offset(origin));
+
+        for pos in [&mut layout.board_label, &mut layout.info, &mut layout.level] {
+            pos.offset(origin);
+        }
+
+        layout
+    }
+
+    pub fn too_small(&self) -> bool {
+        self.rows < MIN_ROWS || self.cols < MIN_COLS
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn side_labels_stay_off_the_rows_of_the_bottom_labels() {
+        for num_seats in 2..=10 {
+            for (rows, cols) in [(ROWS, COLS), (MIN_ROWS, MIN_COLS)] {
+                let layout = Layout::new(num_seats, rows, cols);
+                let rows_of = |side: Side| layout.seats.iter()
+                    .filter(|s| s.side == side)
+                    .flat_map(|s| s.play.row - 1..=s.play.row + 1)
+                    .collect::<Vec<_>>();
+
+                let bottom = rows_of(Side::Bottom);
+
+                for side in [Side::Left, Side::Right] {
+                    assert!(rows_of(side).iter().all(|r| !bottom.contains(r)), "{num_seats} seats in {cols}x{rows}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::{env, str::FromStr};
+
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+
+// Colors the terminal can show, richer colors are brought down to the nearest one it has
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ColorDepth {
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16
+}
+
+// Usual values of the 16 basic colors, terminals are free to change them
+const BASIC: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (128, 0, 0)),
+    (Color::DarkGreen, (0, 128, 0)),
+    (Color::DarkYellow, (128, 128, 0)),
+    (Color::DarkBlue, (0, 0, 128)),
+    (Color::DarkMagenta, (128, 0, 128)),
+    (Color::DarkCyan, (0, 128, 128)),
+    (Color::Grey, (192, 192, 192)),
+    (Color::DarkGrey, (128, 128, 128)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (0, 0, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255))
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl FromStr for ColorDepth {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "truecolor" => Ok(ColorDepth::TrueColor),
+            "256" => Ok(ColorDepth::Ansi256),
+            "16" => Ok(ColorDepth::Ansi16),
+            _ => Err(())
+        }
+    }
+}
+
+impl ColorDepth {
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+
+        // Windows consoles set neither variable but show RGB colors, like Windows Terminal does
+        let windows = env::var_os("WT_SESSION").is_some() || (cfg!(windows) && term.is_empty());
+
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") || windows {
+            ColorDepth::TrueColor
+
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    pub fn convert(self, color: Color) -> Color {
+        let Color::Rgb { r, g, b } = color else {
+            return color;
+        };
+
+        match self {
+            ColorDepth::TrueColor => color,
+            ColorDepth::Ansi256 => Color::AnsiValue(nearest_256((r, g, b))),
+            ColorDepth::Ansi16 => nearest_basic((r, g, b))
+        }
+    }
+
+    // Text that ends up in the color of its background is written in black or white instead
+    pub fn convert_pair(self, background: Color, font: Color) -> (Color, Color) {
+        let (background, font) = (self.convert(background), self.convert(font));
+
+        if self == ColorDepth::TrueColor || background != font {
+            return (background, font);
+        }
+
+        let light = rgb(background).is_some_and(|(r, g, b)| 299 * r as usize + 587 * g as usize + 114 * b as usize > 128_000);
+
+        (background, if light { Color::Black } else { Color::White })
+    }
+}
+
+// Weighted distance that is closer to how different two colors look than the plain one
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> usize {
+    let mean_r = (a.0 as usize + b.0 as usize) / 2;
+    let (dr, dg, db) = (a.0.abs_diff(b.0) as usize, a.1.abs_diff(b.1) as usize, a.2.abs_diff(b.2) as usize);
+
+    (((512 + mean_r) * dr * dr) >> 8) + 4 * dg * dg + (((767 - mean_r) * db * db) >> 8)
+}
+
+fn nearest_basic(color: (u8, u8, u8)) -> Color {
+    BASIC.iter().min_by_key(|(_, value)| distance(color, *value)).map(|(basic, _)| *basic).unwrap()
+}
+
+// Nearest of the 6x6x6 color cube and the gray ramp of the 256 color palette
+fn nearest_256(color: (u8, u8, u8)) -> u8 {
+    let level = |c: u8| (0..6).min_by_key(|i| CUBE_LEVELS[*i].abs_diff(c)).unwrap();
+    let (r, g, b) = (level(color.0), level(color.1), level(color.2));
+    let cube = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let average = (color.0 as usize + color.1 as usize + color.2 as usize) / 3;
+    let gray = (average.saturating_sub(3) / 10).min(23);
+    let gray_value = (8 + 10 * gray) as u8;
+
+    if distance(color, (gray_value, gray_value, gray_value)) < distance(color, cube) {
+        232 + gray as u8
+    } else {
+        16 + 36 * r as u8 + 6 * g as u8 + b as u8
+    }
+}
+
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    BASIC.iter().find(|(basic, _)| *basic == color).map(|(_, value)| *value)
+}
+
+// Consoles without a Unicode font, or set to a locale other than UTF-8, only get plain ASCII
+pub fn detect_ascii() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+
+    // No locale at all is the usual case on Windows, which shows Unicode fine
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"].iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|value| value.to_lowercase());
+
+    is_ascii_console(&term, locale.as_deref())
+}
+
+fn is_ascii_console(term: &str, locale: Option<&str>) -> bool {
+    let non_utf8 = locale.is_some_and(|locale| !(locale.contains("utf-8") || locale.contains("utf8")));
+
+    matches!(term, "linux" | "dumb" | "vt100" | "vt220") || non_utf8
+}
+
+// Box drawing is made of +-| borders, suits become their letters and blocks are shaded with symbols
+pub fn to_ascii(char: char) -> char {
+    match char {
+        c if c.is_ascii() => c,
+        '─' | '━' | '═' | '╸' | '╺' => '-',
+        '│' | '┃' | '║' | '╹' | '╻' => '|',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┏' | '┓' | '┗' | '┛' | '┣' | '┫' | '┳' | '┻' | '╔' | '╗' | '╚' | '╝' => '+',
+        '▄' | '▀' => ' ',
+        '█' | '▓' => '#',
+        '▒' => ':',
+        '♥' => 'h',
+        '♦' => 'd',
+        '♣' => 'c',
+        '♠' => 's',
+        _ => '?'
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_are_brought_down_to_the_nearest_the_terminal_has() {
+        let red = Color::Rgb { r: 250, g: 5, b: 5 };
+        let gray = Color::Rgb { r: 128, g: 128, b: 128 };
+        let green = Color::Rgb { r: 0, g: 100, b: 0 };
+
+        assert_eq!(ColorDepth::TrueColor.convert(red), red);
+        assert_eq!(ColorDepth::Ansi256.convert(red), Color::AnsiValue(196));
+        assert_eq!(ColorDepth::Ansi256.convert(gray), Color::AnsiValue(244));
+        assert_eq!(ColorDepth::Ansi16.convert(red), Color::Red);
+        assert_eq!(ColorDepth::Ansi16.convert(green), Color::DarkGreen);
+
+        // Colors that are not RGB are already in every palette
+        assert_eq!(ColorDepth::Ansi16.convert(Color::Blue), Color::Blue);
+        assert_eq!(ColorDepth::Ansi256.convert(Color::AnsiValue(42)), Color::AnsiValue(42));
+    }
+
+    #[test]
+    fn text_stays_readable_when_it_lands_on_the_color_of_its_background() {
+        let background = Color::Rgb { r: 10, g: 100, b: 10 };
+        let font = Color::Rgb { r: 20, g: 110, b: 20 };
+
+        assert_eq!(ColorDepth::TrueColor.convert_pair(background, font), (background, font));
+        assert_eq!(ColorDepth::Ansi16.convert_pair(background, font), (Color::DarkGreen, Color::White));
+        assert_eq!(ColorDepth::Ansi16.convert_pair(Color::Rgb { r: 250, g: 250, b: 5 }, Color::Rgb { r: 255, g: 240, b: 0 }), (Color::Yellow, Color::Black));
+    }
+
+    #[test]
+    fn plain_consoles_and_other_locales_get_ascii() {
+        assert!(!is_ascii_console("xterm-256color", Some("en_us.utf-8")));
+        assert!(!is_ascii_console("xterm", Some("c.utf8")));
+        assert!(!is_ascii_console("", None));
+
+        assert!(is_ascii_console("linux", Some("en_us.utf-8")));
+        assert!(is_ascii_console("dumb", None));
+        assert!(is_ascii_console("xterm-256color", Some("c")));
+        assert!(is_ascii_console("xterm", Some("en_us.iso-8859-1")));
+
+        assert_eq!(to_ascii('┌'), '+');
+        assert_eq!(to_ascii('♠'), 's');
+        assert_eq!(to_ascii('A'), 'A');
+    }
+}
\ No newline at end of file
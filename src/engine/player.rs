@@ -1,20 +1,20 @@
 use crate::{actor::actor::PokerActor, poker::card::Card};
 
-pub const SMALL_BLIND: usize = 2;
-pub const BIG_BLIND: usize = 5;
-
 pub struct Player {
     pub name: String,
     pub money: usize,
     pub bet: usize,
+    pub ante: usize,
     pub folded: bool,
+    pub sitting_out: bool,
+    pub bought_in: usize, // Chips taken to the table, to tell the profit of cash games
     pub hand: Vec<Card>,
     pub actor: Box<dyn PokerActor>
 }
 
 impl Player {
     pub fn new(name: String, money: usize, actor: Box<dyn PokerActor>) -> Self {
-        Player { name, money, bet: 0, folded: false, hand: vec!(), actor }
+        Player { name, money, bet: 0, ante: 0, folded: false, sitting_out: false, bought_in: money, hand: vec!(), actor }
     }
 
     pub fn give_card(&mut self, card: Card) {
@@ -26,12 +26,22 @@ impl Player {
         self.bet += chips;
     }
 
+    pub fn ante_chips(&mut self, chips: usize) {
+        self.money -= chips;
+        self.ante += chips;
+    }
+
+    pub fn contribution(&self) -> usize {
+        self.bet + self.ante
+    }
+
     pub fn win(&mut self, chips: usize) {
         self.money += chips;
     }
 
     pub fn lose_bet(&mut self) {
         self.bet = 0;
+        self.ante = 0;
     }
 
     pub fn fold(&mut self) {
@@ -42,11 +52,21 @@ impl Player {
         self.folded = false;
     }
 
+    // Out of the hands, either busted or sitting out
     pub fn lost(&self) -> bool {
-        self.money == 0 && self.bet == 0
+        self.sitting_out || (self.money == 0 && self.contribution() == 0)
+    }
+
+    pub fn add_chips(&mut self, chips: usize) {
+        self.money += chips;
+        self.bought_in += chips;
+    }
+
+    pub fn profit(&self) -> isize {
+        (self.money + self.contribution()) as isize - self.bought_in as isize
     }
 
     pub fn is_all_in(&self) -> bool {
-        self.money == 0 && self.bet != 0
+        self.money == 0 && self.contribution() != 0
     }
 }
\ No newline at end of file
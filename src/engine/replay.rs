@@ -0,0 +1,464 @@
+use std::{fs, path::Path, time::Duration};
+
+use crate::{engine::{table::{MAX_SEATS, MIN_SEATS}, timer::Timer}, poker::card::Card};
+
+// Time between steps when playing, from slowest to fastest
+const SPEEDS: [u64; 4] = [2000, 1000, 500, 250];
+
+// State of the table after one line of the hand history
+#[derive(Clone, Debug)]
+pub struct ReplayFrame {
+    pub stacks: Vec<usize>,
+    pub bets: Vec<usize>,
+    pub folded: Vec<bool>,
+    pub board: usize, // The second one once a hand run twice deals it
+    pub flipped: usize,
+    pub turn: Option<usize>,
+    pub message: String
+}
+
+#[derive(Clone, Debug)]
+pub struct ReplayHand {
+    pub id: String,
+    pub game: String,
+    pub dealer: usize,
+    pub names: Vec<String>,
+    pub hands: Vec<Vec<Card>>,
+    pub board: Vec<Card>,
+    pub second_board: Vec<Card>,
+    pub frames: Vec<ReplayFrame>
+}
+
+pub struct Replayer {
+    pub hands: Vec<ReplayHand>,
+    pub hand: usize,
+    pub frame: usize,
+    pub playing: bool,
+    pub speed: usize,
+    pub changed: bool,
+    timer: Timer
+}
+
+fn parse_amount(amount: &str, line: usize) -> Result<usize, String> {
+    amount.trim().parse().map_err(|_| format!("Line {line}: invalid amount {amount}"))
+}
+
+// Cards of every [..] group in the line
+fn parse_card_groups(text: &str, line: usize) -> Result<Vec<Vec<Card>>, String> {
+    text.split('[').skip(1)
+        .map(|group| {
+            group.split(']').next().unwrap_or_default()
+                .split_whitespace()
+                .map(|c| Card::from_short_name(c).ok_or(format!("Line {line}: invalid card {c}")))
+                .collect()
+        })
+        .collect()
+}
+
+impl ReplayHand {
+    fn new(header: &str) -> Self {
+        let id = header.trim_start_matches("PokerStars Hand #").split(':').next().unwrap_or_default();
+        let game = header.split_once(": ").map_or("", |(_, g)| g.split(" - ").next().unwrap_or_default());
+
+        ReplayHand { id: id.into(), game: game.into(), dealer: 0, names: vec!(), hands: vec!(), board: vec!(), second_board: vec!(), frames: vec!() }
+    }
+
+    fn seat(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    fn last_frame(&self) -> ReplayFrame {
+        self.frames.last().cloned().unwrap_or_else(|| {
+            let num_seats = self.names.len();
+
+            ReplayFrame {
+                stacks: vec![0; num_seats],
+                bets: vec![0; num_seats],
+                folded: vec![false; num_seats],
+                board: 0,
+                flipped: 0,
+                turn: None,
+                message: format!("Hand #{}", self.id)
+            }
+        })
+    }
+}
+
+pub fn parse_histories(text: &str) -> Result<Vec<ReplayHand>, String> {
+    let mut hands: Vec<ReplayHand> = vec!();
+
+    // Seats are only read from the header, the summary lists them again
+    let mut in_header = false;
+    let mut in_summary = false;
+    let mut street_bets = vec!();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let num = i + 1;
+
+        if line.starts_with("PokerStars Hand #") {
+            hands.push(ReplayHand::new(line));
+            in_header = true;
+            in_summary = false;
+            continue;
+        }
+
+        let Some(hand) = hands.last_mut() else {
+            continue;
+        };
+
+        if line.is_empty() || in_summary {
+            continue;
+        }
+
+        if let Some(table) = line.strip_prefix("Table '") {
+            let words = table.split_whitespace().collect::<Vec<_>>();
+
+            let num_seats = words.iter()
+                .find_map(|w| w.strip_suffix("-max"))
+                .map(|n| parse_amount(n, num))
+                .transpose()?
+                .filter(|n| (MIN_SEATS..=MAX_SEATS).contains(n))
+                .ok_or(format!("Line {num}: tables must have between {MIN_SEATS} and {MAX_SEATS} seats"))?;
+
+            let button = words.iter()
+                .find_map(|w| w.strip_prefix('#'))
+                .map(|n| parse_amount(n, num))
+                .transpose()?
+                .filter(|n| (1..=num_seats).contains(n))
+                .ok_or(format!("Line {num}: missing button seat"))?;
+
+            hand.dealer = button - 1;
+            hand.names = vec![String::new(); num_seats];
+            hand.hands = vec![vec!(); num_seats];
+            street_bets = vec![0; num_seats];
+            continue;
+        }
+
+        if hand.names.is_empty() {
+            return Err(format!("Line {num}: the table line is missing"));
+        }
+
+        if let Some(seat) = line.strip_prefix("Seat ") && in_header {
+            let (seat, player) = seat.split_once(": ").ok_or(format!("Line {num}: invalid seat"))?;
+            let (name, stack) = player.rsplit_once(" (").ok_or(format!("Line {num}: invalid seat"))?;
+
+            let seat = parse_amount(seat, num)?.checked_sub(1).filter(|s| *s < hand.names.len()).ok_or(format!("Line {num}: invalid seat"))?;
+            let stack = parse_amount(stack.trim_end_matches(" in chips)"), num)?;
+
+            hand.names[seat] = name.into();
+
+            if hand.frames.is_empty() {
+                let first = hand.last_frame();
+                hand.frames.push(first);
+            }
+
+            hand.frames[0].stacks[seat] = stack;
+            continue;
+        }
+
+        in_header = false;
+
+        let mut frame = hand.last_frame();
+        frame.turn = None;
+
+        if line == "*** SUMMARY ***" {
+            in_summary = true;
+            continue;
+        }
+
+        if line.starts_with("*** ") {
+            let street = line.split(" [").next().unwrap_or_default().trim_matches('*').trim();
+
+            frame.message = match street {
+                "FLOP" | "TURN" | "RIVER" => {
+                    let cards = parse_card_groups(line, num)?.pop().unwrap_or_default();
+                    hand.board.extend(cards);
+
+                    frame.flipped = hand.board.len();
+                    street_bets.iter_mut().for_each(|b| *b = 0);
+
+                    street[..1].to_string() + &street[1..].to_lowercase()
+                },
+
+                // The second board is written whole, with the cards it shares with the first one
+                "SECOND FLOP" | "SECOND TURN" | "SECOND RIVER" => {
+                    hand.second_board = parse_card_groups(line, num)?.concat();
+
+                    frame.board = 1;
+                    frame.flipped = hand.second_board.len();
+
+                    format!("Second {}", street["SECOND ".len()..].to_lowercase())
+                },
+
+                "SHOW DOWN" => "Showdown".into(),
+
+                "FIRST SHOW DOWN" => {
+                    frame.board = 0;
+                    frame.flipped = hand.board.len();
+
+                    "First showdown".into()
+                },
+
+                "SECOND SHOW DOWN" => {
+                    frame.board = 1;
+                    frame.flipped = hand.second_board.len();
+
+                    "Second showdown".into()
+                },
+
+                _ => continue
+            };
+
+        } else if let Some(dealt) = line.strip_prefix("Dealt to ") {
+            let (name, _) = dealt.rsplit_once(" [").ok_or(format!("Line {num}: invalid hole cards"))?;
+            let seat = hand.seat(name).ok_or(format!("Line {num}: unknown player {name}"))?;
+
+            hand.hands[seat] = parse_card_groups(dealt, num)?.pop().unwrap_or_default();
+            continue;
+
+        } else if let Some(uncalled) = line.strip_prefix("Uncalled bet (") {
+            let (amount, name) = uncalled.split_once(") returned to ").ok_or(format!("Line {num}: invalid uncalled bet"))?;
+            let seat = hand.seat(name).ok_or(format!("Line {num}: unknown player {name}"))?;
+            let amount = parse_amount(amount, num)?;
+
+            frame.stacks[seat] += amount;
+            frame.bets[seat] = frame.bets[seat].saturating_sub(amount);
+            frame.message = line.into();
+
+        } else if let Some((name, collected)) = line.split_once(" collected ") && let Some(seat) = hand.seat(name) {
+            let amount = parse_amount(collected.split(" from ").next().unwrap_or_default(), num)?;
+
+            frame.bets.iter_mut().for_each(|b| *b = 0);
+            frame.stacks[seat] += amount;
+            frame.message = line.into();
+
+        } else if let Some((name, action)) = line.rsplit_once(": ") && let Some(seat) = hand.seat(name) {
+            let (verb, amount) = action.trim_end_matches(" and is all-in").split_once(' ').unwrap_or((action, ""));
+
+            let chips = match verb {
+                "posts" => {
+                    let (blind, amount) = amount.rsplit_once(' ').ok_or(format!("Line {num}: invalid post"))?;
+                    let amount = parse_amount(amount, num)?;
+
+                    if blind == "the ante" {
+                        frame.stacks[seat] -= amount.min(frame.stacks[seat]);
+                        0
+                    } else {
+                        amount
+                    }
+                },
+
+                "folds" => {
+                    frame.folded[seat] = true;
+                    0
+                },
+
+                "checks" => 0,
+                "calls" | "bets" => parse_amount(amount, num)?,
+
+                "raises" => {
+                    let (_, to) = amount.split_once(" to ").ok_or(format!("Line {num}: invalid raise"))?;
+                    parse_amount(to, num)?.saturating_sub(street_bets[seat])
+                },
+
+                _ => continue // Shown hands and chat are not replayed
+            };
+
+            let chips = chips.min(frame.stacks[seat]);
+
+            frame.stacks[seat] -= chips;
+            frame.bets[seat] += chips;
+            street_bets[seat] += chips;
+
+            frame.turn = Some(seat);
+            frame.message = line.into();
+
+        } else {
+            continue;
+        }
+
+        hand.frames.push(frame);
+    }
+
+    hands.retain(|h| !h.frames.is_empty());
+
+    if hands.is_empty() {
+        return Err("No hands to replay".into());
+    }
+
+    Ok(hands)
+}
+
+impl Replayer {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {e}", path.display()))?;
+        let hands = parse_histories(&content).map_err(|e| format!("Unable to parse {}: {e}", path.display()))?;
+
+        Ok(Replayer { hands, hand: 0, frame: 0, playing: false, speed: 1, changed: true, timer: Timer::new(Duration::ZERO) })
+    }
+
+    pub fn current_hand(&self) -> &ReplayHand {
+        &self.hands[self.hand]
+    }
+
+    pub fn current_frame(&self) -> &ReplayFrame {
+        &self.current_hand().frames[self.frame]
+    }
+
+    fn go_to(&mut self, hand: usize, frame: usize) {
+        self.changed |= (hand, frame) != (self.hand, self.frame);
+        self.hand = hand;
+        self.frame = frame;
+    }
+
+    pub fn step_forward(&mut self) {
+        if self.frame + 1 < self.current_hand().frames.len() {
+            self.go_to(self.hand, self.frame + 1);
+
+        } else if self.hand + 1 < self.hands.len() {
+            self.go_to(self.hand + 1, 0);
+
+        } else {
+            self.playing = false;
+        }
+    }
+
+    pub fn step_back(&mut self) {
+        if self.frame > 0 {
+            self.go_to(self.hand, self.frame - 1);
+
+        } else if self.hand > 0 {
+            self.go_to(self.hand - 1, self.hands[self.hand - 1].frames.len() - 1);
+        }
+    }
+
+    // Streets are told apart by the board and its number of community cards
+    pub fn next_street(&mut self) {
+        let frames = &self.current_hand().frames;
+        let street = (frames[self.frame].board, frames[self.frame].flipped);
+
+        let next = frames.iter().skip(self.frame).position(|f| (f.board, f.flipped) > street).map(|i| self.frame + i);
+        self.go_to(self.hand, next.unwrap_or(frames.len() - 1));
+    }
+
+    pub fn previous_street(&mut self) {
+        let frames = &self.current_hand().frames;
+        let street_start = |f: &ReplayFrame| frames.iter().position(|s| (s.board, s.flipped) == (f.board, f.flipped)).unwrap_or(0);
+
+        let start = street_start(&frames[self.frame]);
+
+        let previous = if start < self.frame || start == 0 {
+            start
+        } else {
+            street_start(&frames[start - 1])
+        };
+
+        self.go_to(self.hand, previous);
+    }
+
+    pub fn next_hand(&mut self) {
+        self.go_to((self.hand + 1).min(self.hands.len() - 1), 0);
+    }
+
+    pub fn previous_hand(&mut self) {
+        self.go_to(self.hand.saturating_sub(1), 0);
+    }
+
+    pub fn toggle_play(&mut self) {
+        self.playing = !self.playing;
+        self.changed = true;
+        self.restart_timer();
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+        self.changed = true;
+        self.restart_timer();
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+        self.changed = true;
+        self.restart_timer();
+    }
+
+    // Speed as a multiple of the slowest one
+    pub fn speed_factor(&self) -> u64 {
+        SPEEDS[0] / SPEEDS[self.speed]
+    }
+
+    fn restart_timer(&mut self) {
+        self.timer = Timer::new_started(Duration::from_millis(SPEEDS[self.speed]));
+    }
+
+    pub fn update(&mut self) {
+        if self.playing && self.timer.done() {
+            self.step_forward();
+            self.restart_timer();
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::history::tests::{run_twice_hand, showdown_hand};
+
+    fn names(cards: &[Card]) -> String {
+        cards.iter().map(Card::short_name).collect::<Vec<_>>().join(" ")
+    }
+
+    fn messages(hand: &ReplayHand) -> Vec<&str> {
+        hand.frames.iter().map(|f| f.message.as_str()).collect()
+    }
+
+    #[test]
+    fn hand_history_is_replayed_line_by_line() {
+        let hands = parse_histories(&showdown_hand()).unwrap();
+        let hand = &hands[0];
+
+        assert_eq!(hands.len(), 1);
+        assert_eq!(hand.names, vec!("Alice", "Bob", "Carol"));
+        assert_eq!(names(&hand.hands[2]), "Qh Qs");
+        assert_eq!(names(&hand.board), "2c 7d 9h Jc 4s");
+
+        let messages = messages(hand);
+
+        for street in ["Flop", "Turn", "River", "Showdown", "Carol collected 65 from pot"] {
+            assert!(messages.contains(&street), "{street}");
+        }
+
+        let first = &hand.frames[0];
+        let last = hand.frames.last().unwrap();
+
+        assert_eq!(first.stacks, vec!(1000, 1000, 1000));
+        assert_eq!(last.stacks, vec!(970, 995, 1035));
+        assert_eq!((last.board, last.flipped), (0, 5));
+    }
+
+    #[test]
+    fn second_board_of_a_hand_run_twice_is_replayed() {
+        let hands = parse_histories(&run_twice_hand()).unwrap();
+        let hand = &hands[0];
+
+        assert_eq!(names(&hand.board), "2c 7d 9h Jc 4s");
+        assert_eq!(names(&hand.second_board), "Kd 2h 7c 9s 4d");
+
+        // Every frame but the first one and the actions of the players
+        let streets = hand.frames.iter().skip(1)
+            .filter(|f| f.turn.is_none())
+            .map(|f| (f.message.as_str(), f.board, f.flipped))
+            .collect::<Vec<_>>();
+
+        assert_eq!(streets, vec!(
+            ("Flop", 0, 3), ("Turn", 0, 4), ("River", 0, 5),
+            ("Second flop", 1, 3), ("Second turn", 1, 4), ("Second river", 1, 5),
+            ("First showdown", 0, 5), ("Alice collected 1000 from pot", 0, 5),
+            ("Second showdown", 1, 5), ("Bob collected 1000 from pot", 1, 5)
+        ));
+
+        assert_eq!(hand.frames.last().unwrap().stacks, vec!(1000, 1000));
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use crate::actor::action::Action;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActionError {
+    CannotAct,
+    CannotCheck { call: usize },
+    NothingToCall,
+    AlreadyBet,
+    NoBetToRaise,
+    NotEnoughChips { stack: usize },
+    RaiseTooSmall { min_raise_to: usize },
+    RaiseNotAllowed
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::CannotAct => write!(f, "No action is possible right now"),
+            ActionError::CannotCheck { call } => write!(f, "Cannot check, there are {call} chips to call"),
+            ActionError::NothingToCall => write!(f, "There is nothing to call, check instead"),
+            ActionError::AlreadyBet => write!(f, "There is already a bet, raise instead"),
+            ActionError::NoBetToRaise => write!(f, "There is no bet to raise, bet instead"),
+            ActionError::NotEnoughChips { stack } => write!(f, "Not enough chips, the stack is {stack}"),
+            ActionError::RaiseTooSmall { min_raise_to } => write!(f, "The bet must be at least {min_raise_to} unless going all-in"),
+            ActionError::RaiseNotAllowed => write!(f, "Betting is not open to a raise, only call or fold")
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+// Amounts are totals on the current street, like the ones in Action
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LegalActions {
+    pub current_bet: usize,
+    pub call: usize, // 0 means check
+    pub opened: bool, // Whether there is a bet to raise or the first one has to be made
+    pub can_raise: bool,
+    pub min_raise_to: usize,
+    pub max_raise_to: usize,
+    pub all_in_raises: bool, // Whether going all-in is a raise or just a call
+    pub pot: usize
+}
+
+impl LegalActions {
+    pub fn is_all_in(&self, raise_to: usize) -> bool {
+        raise_to >= self.max_raise_to
+    }
+
+    pub fn call_or_check(&self) -> Action {
+        if self.call == 0 { Action::Check } else { Action::Call }
+    }
+
+    // Bet or raise to the given total, clamped to what is legal
+    pub fn raise_to(&self, raise_to: usize) -> Action {
+        if !self.can_raise {
+            return self.call_or_check();
+        }
+
+        let raise_to = raise_to.clamp(self.min_raise_to, self.max_raise_to);
+
+        if self.is_all_in(raise_to) {
+            Action::AllIn
+
+        } else if self.opened {
+            Action::RaiseTo(raise_to)
+
+        } else {
+            Action::Bet(raise_to)
+        }
+    }
+
+    // Common sizes: min raise, double, triple, pot and double pot
+    pub fn raise_presets(&self) -> [usize; 5] {
+        let min_raise = self.min_raise_to.saturating_sub(self.current_bet);
+        let pot_raise = self.pot + self.call;
+
+        [
+            self.current_bet + min_raise,
+            self.current_bet + min_raise * 2,
+            self.current_bet + min_raise * 3,
+            self.current_bet + pot_raise,
+            self.current_bet + pot_raise * 2
+        ].map(|to| to.clamp(self.min_raise_to, self.max_raise_to))
+    }
+}
@@ -0,0 +1,211 @@
+use std::{fs, io::ErrorKind, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{engine::{config::TableConfig, state::GameState}, poker::card::Card};
+
+pub const DEFAULT_SAVE_PATH: &str = "console-ace-save.toml";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub name: Option<String>, // Cash games seat new players
+    pub money: usize,
+    pub bet: usize,
+    pub ante: usize,
+    pub folded: bool,
+    #[serde(default)]
+    pub sitting_out: bool,
+    pub bought_in: Option<usize>,
+    pub hand: Vec<Card>,
+    pub acted: Option<usize>
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SavedSchedule {
+    pub level: usize,
+    pub hands_played: usize,
+    pub elapsed: Option<Duration>
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SavedCash {
+    pub next_opponent: usize
+}
+
+// Plays are computed again from the cards, winners cannot since the pots are already paid
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SavedShowdown {
+    pub winners: Vec<usize>,
+    pub contenders: Vec<usize>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedTable {
+    pub state: GameState,
+    pub seed: u64, // The table RNG is reseeded with it when saving, so resuming goes on exactly like the saved game
+    pub dealer: usize,
+    pub small_blind: usize,
+    pub big_blind: usize,
+    pub ante: usize,
+    pub dead_money: usize,
+    pub current_bet: usize,
+    pub last_raise: usize,
+    pub full_bet: usize,
+    pub street_bet: usize,
+    pub deck: Vec<Card>,
+    pub board: Vec<Card>,
+    #[serde(default)]
+    pub busted: Vec<usize>,
+    #[serde(default)]
+    pub hand_number: usize,
+    pub straddler: Option<usize>,
+    pub second_board: Option<Vec<Card>>,
+    #[serde(default)]
+    pub runout_start: usize,
+    pub players: Vec<SavedPlayer>,
+    pub schedule: Option<SavedSchedule>,
+    pub cash: Option<SavedCash>,
+    pub showdown: Option<SavedShowdown>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub config: TableConfig,
+    pub table: SavedTable
+}
+
+impl SavedGame {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {e}", path.display()))?;
+        let saved: SavedGame = toml::from_str(&content).map_err(|e| format!("Unable to parse {}: {e}", path.display()))?;
+
+        saved.validate()?;
+
+        Ok(saved)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let content = toml::to_string(self).map_err(|e| format!("Unable to save the game: {e}"))?;
+
+        fs::write(path, content).map_err(|e| format!("Unable to write {}: {e}", path.display()))
+    }
+
+    // Nothing to remove is fine, the game may never have been saved
+    pub fn remove(path: &Path) -> Result<(), String> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(format!("Unable to remove {}: {e}", path.display())),
+            _ => Ok(())
+        }
+    }
+
+    // Seats and cards the state points at must exist, the table indexes them as they are
+    fn state_is_valid(&self) -> bool {
+        let table = &self.table;
+        let is_seat = |seat: usize| seat < table.players.len();
+        let is_street = |num_flipped: usize, board: &[Card]| matches!(num_flipped, 0 | 3..=5) && num_flipped <= board.len();
+
+        let second_board = match &table.second_board {
+            Some(board) if board.len() != 5 || table.runout_start > 5 => return false,
+            Some(board) => Some(board.as_slice()),
+            None => None
+        };
+
+        let state = match table.state {
+            GameState::Straddle(seat) => is_seat(seat),
+            GameState::Round(num_flipped, turn, ..) => is_seat(turn) && is_street(num_flipped, &table.board),
+            GameState::Runout(num_flipped, 0) => is_street(num_flipped, &table.board),
+            GameState::Runout(num_flipped, 1) => second_board.is_some_and(|b| is_street(num_flipped, b)),
+            GameState::Runout(..) => false,
+            _ => true
+        };
+
+        let showdown = table.showdown.as_ref().is_none_or(|s| s.winners.iter().chain(&s.contenders).all(|seat| is_seat(*seat)));
+
+        state && showdown && table.straddler.is_none_or(is_seat)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.config.validate()?;
+
+        if self.table.players.len() != self.config.seats.len() || self.table.dealer >= self.table.players.len() {
+            return Err("The saved players do not match the saved seats".into());
+        }
+
+        if self.table.busted.iter().any(|s| *s >= self.table.players.len()) {
+            return Err("The saved knocked out players do not match the saved seats".into());
+        }
+
+        if !self.state_is_valid() {
+            return Err("The saved state does not match the saved seats and board".into());
+        }
+
+        let second_board = self.table.second_board.as_ref().map_or(0, |b| b.len().saturating_sub(self.table.runout_start));
+        let cards = self.table.deck.len() + self.table.board.len() + second_board + self.table.players.iter().map(|p| p.hand.len()).sum::<usize>();
+
+        if cards != 52 {
+            return Err("The saved game does not hold a full deck".into());
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{config::{ActorKind, SeatConfig}, controls::Controls, table::Table};
+
+    fn saved_game() -> (Table, SavedGame) {
+        // The simple actors post their blinds without running the AI
+        let seats = (0..4).map(|seat| SeatConfig { actor: if seat == 0 { ActorKind::Human } else { ActorKind::Simple }, ..SeatConfig::new(seat) }).collect();
+        let config = TableConfig { seed: Some(3), seats, ..TableConfig::default() };
+        let mut table = Table::from_config(&config);
+        let mut controls = Controls::new();
+
+        // Up to the first decision of the pre-flop
+        table.start();
+
+        while !matches!(table.state, GameState::Round(0, _, true, true, _)) {
+            table.update(&mut controls);
+        }
+
+        let saved = SavedGame { config, table: table.save() };
+
+        (table, saved)
+    }
+
+    #[test]
+    fn saved_game_resumes_where_it_was() {
+        let (mut table, saved) = saved_game();
+        let path = std::env::temp_dir().join(format!("console-ace-save-test-{}.toml", std::process::id()));
+
+        saved.write(&path).unwrap();
+        let loaded = SavedGame::load(&path);
+        SavedGame::remove(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        let mut resumed = Table::from_saved(&loaded.config, loaded.table);
+
+        // Saving again reseeds both from the same RNG, so everything matches
+        assert_eq!(toml::to_string(&resumed.save()).unwrap(), toml::to_string(&table.save()).unwrap());
+    }
+
+    #[test]
+    fn saved_state_pointing_out_of_the_table_is_rejected() {
+        let (_, mut saved) = saved_game();
+        assert_eq!(saved.validate(), Ok(()));
+
+        let num_seats = saved.table.players.len();
+
+        for state in [GameState::Round(0, num_seats, true, true, true), GameState::Round(6, 0, true, true, true), GameState::Straddle(num_seats), GameState::Runout(5, 1)] {
+            let mut saved = saved.clone();
+            saved.table.state = state;
+
+            assert!(saved.validate().is_err(), "{state:?}");
+        }
+
+        saved.table.straddler = Some(num_seats);
+        assert!(saved.validate().is_err());
+    }
+}
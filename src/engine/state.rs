@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum GameState {
-    MainMenu(bool), Dealing, Round(usize, usize, bool, bool, bool), Resolving, Collecting, End(bool)
+    MainMenu(bool), Dealing, Straddle(usize), Round(usize, usize, bool, bool, bool), Runout(usize, usize), Resolving, Collecting, End(bool)
 }
\ No newline at end of file
@@ -0,0 +1,374 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{actor::action::Action, engine::event::{Blind, GameEvent, Street}};
+
+pub const DEFAULT_STATS_PATH: &str = "console-ace-stats.toml";
+
+#[derive(Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStats {
+    pub hands: usize,
+    pub vpip: usize,
+    pub pfr: usize,
+    pub three_bet_chances: usize,
+    pub three_bets: usize,
+    pub aggressive: usize, // Bets and raises after the flop
+    pub calls: usize, // Calls after the flop
+    pub saw_flop: usize,
+    pub showdowns: usize,
+    pub showdowns_won: usize,
+    pub net_big_blinds: f64
+}
+
+// What a seat did in the current hand
+#[derive(Clone, Copy, Default, Debug)]
+struct HandStats {
+    dealt: bool,
+    folded: bool,
+    vpip: bool,
+    pfr: bool,
+    three_bet_chance: bool,
+    three_bet: bool,
+    aggressive: usize,
+    calls: usize,
+    saw_flop: bool,
+    showdown: bool,
+    put_in: usize,
+    won: usize
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StatsFile {
+    players: BTreeMap<String, PlayerStats>
+}
+
+// Keeps the stats of every player by name, so they carry over between sessions
+pub struct StatsTracker {
+    pub players: BTreeMap<String, PlayerStats>,
+    names: Vec<String>,
+    seats: Vec<HandStats>,
+    street_bets: Vec<usize>,
+    street: Street,
+    raises: usize, // Raises made so far on the street, the big blind counts as the first bet
+    big_blind: usize,
+    started: bool
+}
+
+fn pct(count: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { 100.0 * count as f64 / total as f64 }
+}
+
+impl PlayerStats {
+    pub fn vpip(&self) -> f64 {
+        pct(self.vpip, self.hands)
+    }
+
+    pub fn pfr(&self) -> f64 {
+        pct(self.pfr, self.hands)
+    }
+
+    pub fn three_bet(&self) -> f64 {
+        pct(self.three_bets, self.three_bet_chances)
+    }
+
+    pub fn aggression_factor(&self) -> f64 {
+        if self.calls == 0 { self.aggressive as f64 } else { self.aggressive as f64 / self.calls as f64 }
+    }
+
+    // Went to showdown, out of the hands where the flop was seen
+    pub fn wtsd(&self) -> f64 {
+        pct(self.showdowns, self.saw_flop)
+    }
+
+    // Won at showdown, out of the showdowns
+    pub fn wsd(&self) -> f64 {
+        pct(self.showdowns_won, self.showdowns)
+    }
+
+    pub fn bb_per_100(&self) -> f64 {
+        if self.hands == 0 { 0.0 } else { 100.0 * self.net_big_blinds / self.hands as f64 }
+    }
+}
+
+impl StatsTracker {
+    pub fn new(names: Vec<String>) -> Self {
+        let num_seats = names.len();
+
+        StatsTracker {
+            players: BTreeMap::new(),
+            names,
+            seats: vec![HandStats::default(); num_seats],
+            street_bets: vec![0; num_seats],
+            street: Street::PreFlop,
+            raises: 0,
+            big_blind: 0,
+            started: false
+        }
+    }
+
+    // Stats from previous sessions, a missing file just means there are none yet
+    pub fn load(&mut self, path: &Path) -> Result<(), String> {
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let content = fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {e}", path.display()))?;
+        let file: StatsFile = toml::from_str(&content).map_err(|e| format!("Unable to parse {}: {e}", path.display()))?;
+
+        self.players = file.players;
+
+        Ok(())
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let file = StatsFile { players: self.players.clone() };
+        let content = toml::to_string(&file).map_err(|e| format!("Unable to save the stats: {e}"))?;
+
+        fs::write(path, content).map_err(|e| format!("Unable to write {}: {e}", path.display()))
+    }
+
+    fn record_action(&mut self, seat: usize, action: &Action, amount: usize) {
+        let facing = self.street_bets.iter().copied().max().unwrap_or(0);
+
+        self.street_bets[seat] += amount;
+        self.seats[seat].put_in += amount;
+
+        let raised = self.street_bets[seat] > facing;
+        let stats = &mut self.seats[seat];
+
+        if self.street == Street::PreFlop {
+            // Facing a single raise is a chance to 3-bet
+            if self.raises == 2 {
+                stats.three_bet_chance = true;
+                stats.three_bet |= raised;
+            }
+
+            stats.vpip |= amount > 0;
+            stats.pfr |= raised;
+
+        } else if raised {
+            stats.aggressive += 1;
+
+        } else if amount > 0 {
+            stats.calls += 1;
+        }
+
+        if raised {
+            self.raises += 1;
+        }
+
+        if *action == Action::Fold {
+            self.seats[seat].folded = true;
+        }
+    }
+
+    fn finish_hand(&mut self) {
+        let big_blind = self.big_blind.max(1) as f64;
+
+        for (seat, hand) in self.seats.iter().enumerate().filter(|(_, s)| s.dealt) {
+            let stats = self.players.entry(self.names[seat].clone()).or_default();
+
+            stats.hands += 1;
+            stats.vpip += hand.vpip as usize;
+            stats.pfr += hand.pfr as usize;
+            stats.three_bet_chances += hand.three_bet_chance as usize;
+            stats.three_bets += hand.three_bet as usize;
+            stats.aggressive += hand.aggressive;
+            stats.calls += hand.calls;
+            stats.saw_flop += hand.saw_flop as usize;
+            stats.showdowns += hand.showdown as usize;
+            stats.showdowns_won += (hand.showdown && hand.won > 0) as usize;
+            stats.net_big_blinds += (hand.won as f64 - hand.put_in as f64) / big_blind;
+        }
+    }
+
+    // Records an event, returning true once a hand is finished and the stats changed
+    pub fn record(&mut self, event: &GameEvent) -> bool {
+        // A hand resumed from a save was started before, so it is left out, seats still change between hands
+        if !self.started && !matches!(event, GameEvent::HandStarted { .. } | GameEvent::PlayerJoined { .. }) {
+            return false;
+        }
+
+        match event {
+            GameEvent::HandStarted { big_blind, stacks, .. } => {
+                self.seats = stacks.iter().map(|s| HandStats { dealt: *s > 0, ..Default::default() }).collect();
+                self.street_bets = vec![0; stacks.len()];
+                self.street = Street::PreFlop;
+                self.raises = 0;
+                self.big_blind = *big_blind;
+                self.started = true;
+            },
+
+            GameEvent::BlindPosted { seat, blind, amount } => {
+                self.seats[*seat].put_in += amount;
+
+                // Antes are not part of the betting
+                if *blind != Blind::Ante {
+                    self.street_bets[*seat] += amount;
+                    self.raises = 1;
+                }
+            },
+
+            GameEvent::ActionTaken { seat, action, amount } => {
+                self.record_action(*seat, action, *amount);
+            },
+
+            GameEvent::StreetDealt { board: 0, street, .. } => {
+                self.street = *street;
+                self.street_bets.iter_mut().for_each(|b| *b = 0);
+                self.raises = 0;
+
+                if *street == Street::Flop {
+                    self.seats.iter_mut().filter(|s| s.dealt && !s.folded).for_each(|s| s.saw_flop = true);
+                }
+            },
+
+            GameEvent::Showdown { hands, .. } if hands.len() > 1 => {
+                hands.iter().for_each(|(seat, _)| self.seats[*seat].showdown = true);
+            },
+
+            GameEvent::UncalledBetReturned { seat, amount } => {
+                self.seats[*seat].put_in -= amount;
+            },
+
+            GameEvent::PotAwarded { seat, amount, .. } => {
+                self.seats[*seat].won += amount;
+            },
+
+            GameEvent::PlayerJoined { seat, name } => {
+                self.names[*seat] = name.clone();
+            },
+
+            GameEvent::HandFinished => {
+                self.started = false;
+                self.finish_hand();
+
+                return true;
+            },
+
+            GameEvent::LevelStarted { .. } |
+            GameEvent::BombPotStarted { .. } |
+            GameEvent::HoleCardsDealt { .. } |
+            GameEvent::RunoutStarted { .. } |
+            GameEvent::StreetDealt { .. } |
+            GameEvent::ActionRejected { .. } |
+            GameEvent::Showdown { .. } |
+            GameEvent::PlayerEliminated { .. } |
+            GameEvent::ChipsAdded { .. } |
+            GameEvent::SatOut { .. } |
+            GameEvent::SatIn { .. } => {}
+        }
+
+        false
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(num_seats: usize) -> StatsTracker {
+        StatsTracker::new(["Alice", "Bob", "Carol", "Dave"][..num_seats].iter().map(|n| n.to_string()).collect())
+    }
+
+    fn record(tracker: &mut StatsTracker, events: Vec<GameEvent>) {
+        let num_seats = tracker.names.len();
+        let started = GameEvent::HandStarted { dealer: 0, small_blind: 5, big_blind: 10, stacks: vec![1000; num_seats] };
+        let events = [vec!(started), events, vec!(GameEvent::HandFinished)].concat();
+
+        for (i, event) in events.iter().enumerate() {
+            assert_eq!(tracker.record(event), i == events.len() - 1);
+        }
+    }
+
+    fn blind(seat: usize, blind: Blind, amount: usize) -> GameEvent {
+        GameEvent::BlindPosted { seat, blind, amount }
+    }
+
+    fn action(seat: usize, action: Action, amount: usize) -> GameEvent {
+        GameEvent::ActionTaken { seat, action, amount }
+    }
+
+    fn flop() -> GameEvent {
+        GameEvent::StreetDealt { board: 0, street: Street::Flop, cards: vec!() }
+    }
+
+    fn stats<'a>(tracker: &'a StatsTracker, name: &str) -> &'a PlayerStats {
+        &tracker.players[name]
+    }
+
+    #[test]
+    fn raise_and_three_bet_before_the_flop() {
+        let mut tracker = tracker(3);
+
+        record(&mut tracker, vec!(
+            blind(1, Blind::Small, 5),
+            blind(2, Blind::Big, 10),
+            action(0, Action::RaiseTo(30), 30),
+            action(1, Action::RaiseTo(90), 85),
+            action(2, Action::Fold, 0),
+            action(0, Action::Call, 60),
+            flop(),
+            action(1, Action::Bet(100), 100),
+            action(0, Action::Fold, 0),
+            GameEvent::UncalledBetReturned { seat: 1, amount: 100 },
+            GameEvent::PotAwarded { pot: 0, seat: 1, amount: 190 }
+        ));
+
+        let (alice, bob, carol) = (stats(&tracker, "Alice"), stats(&tracker, "Bob"), stats(&tracker, "Carol"));
+
+        assert_eq!((alice.hands, alice.vpip, alice.pfr, alice.three_bet_chances, alice.three_bets), (1, 1, 1, 0, 0));
+        assert_eq!((bob.vpip, bob.pfr, bob.three_bet_chances, bob.three_bets), (1, 1, 1, 1));
+        assert_eq!((carol.vpip, carol.pfr, carol.three_bet_chances, carol.three_bets), (0, 0, 0, 0));
+
+        assert_eq!((alice.saw_flop, bob.saw_flop, carol.saw_flop), (1, 1, 0));
+        assert_eq!((bob.aggressive, bob.showdowns), (1, 0));
+
+        assert_eq!(alice.net_big_blinds, -9.0);
+        assert_eq!(bob.net_big_blinds, 10.0);
+        assert_eq!(carol.net_big_blinds, -1.0);
+    }
+
+    #[test]
+    fn straddle_is_a_blind_and_the_raise_over_it_opens_the_betting() {
+        let mut tracker = tracker(4);
+
+        record(&mut tracker, vec!(
+            blind(1, Blind::Small, 5),
+            blind(2, Blind::Big, 10),
+            blind(3, Blind::Straddle, 20),
+            action(0, Action::Call, 20),
+            action(1, Action::RaiseTo(60), 55),
+            action(2, Action::Fold, 0),
+            action(3, Action::RaiseTo(180), 160),
+            action(0, Action::Fold, 0),
+            action(1, Action::Fold, 0),
+            GameEvent::UncalledBetReturned { seat: 3, amount: 120 },
+            GameEvent::PotAwarded { pot: 0, seat: 3, amount: 150 }
+        ));
+
+        let [alice, bob, carol, dave] = ["Alice", "Bob", "Carol", "Dave"].map(|name| stats(&tracker, name));
+
+        // Calling the straddle is a limp, raising over it is the first raise
+        assert_eq!((alice.vpip, alice.pfr, alice.three_bet_chances), (1, 0, 0));
+        assert_eq!((bob.vpip, bob.pfr, bob.three_bet_chances), (1, 1, 0));
+
+        // Facing that single raise is a chance to 3-bet, for the straddle too
+        assert_eq!((carol.vpip, carol.three_bet_chances, carol.three_bets), (0, 1, 0));
+        assert_eq!((dave.vpip, dave.pfr, dave.three_bet_chances, dave.three_bets), (1, 1, 1, 1));
+
+        assert_eq!(dave.net_big_blinds, 9.0);
+    }
+
+    #[test]
+    fn hands_started_before_are_left_out() {
+        let mut tracker = tracker(2);
+
+        assert!(!tracker.record(&action(0, Action::Call, 5)));
+        assert!(!tracker.record(&GameEvent::HandFinished));
+        assert!(tracker.players.is_empty());
+    }
+}
@@ -0,0 +1,1472 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{actor::{action::Action, actor::ActorInfo}, engine::{blinds::BlindSchedule, cash::CashGame, config::TableConfig, controls::Controls, event::{Blind, GameEvent, Street}, player::Player, rules::{ActionError, LegalActions}, save::{SavedCash, SavedPlayer, SavedSchedule, SavedShowdown, SavedTable}, state::GameState}, poker::{card::Card, deck::Deck, play::{analyze_play, Play}}};
+
+pub struct Showdown {
+    pub plays: Vec<Play>,
+    pub winners: HashSet<usize>,
+    pub contenders: HashSet<usize>
+}
+
+pub struct Table {
+    pub deck: Deck,
+    pub state: GameState,
+    pub players: Vec<Player>,
+    pub board: Vec<Card>,
+    pub dealer: usize,
+    pub small_blind: usize,
+    pub big_blind: usize,
+    pub ante: usize,
+    pub big_blind_ante: bool,
+    pub dead_money: usize,
+    pub schedule: Option<BlindSchedule>,
+    pub cash: Option<CashGame>,
+    pub current_bet: usize,
+    pub last_raise: usize,
+    pub full_bet: usize,
+    pub street_bet: usize,
+    pub showdown: Option<Showdown>,
+    pub run_it_twice: bool,
+    pub straddle: bool,
+    pub bomb_pot_every: usize,
+    pub bomb_pot_ante: usize,
+    pub hand_number: usize,
+    pub straddler: Option<usize>,
+    pub second_board: Option<Vec<Card>>,
+    pub runout_start: usize, // Cards of the board already dealt when the runout started, both boards share them
+    pub busted: Vec<usize>, // Seats in the order they were knocked out
+    rng: StdRng,
+    events: Vec<GameEvent>,
+
+    // Full bet each player was facing when they last acted on this street
+    acted: Vec<Option<usize>>
+}
+
+pub const MIN_SEATS: usize = 2;
+pub const MAX_SEATS: usize = 10;
+
+fn next_flipped(num_flipped: usize) -> usize {
+    if num_flipped == 0 { 3 } else { num_flipped + 1 }
+}
+
+impl Table {
+    pub fn new(players: Vec<Player>, small_blind: usize, big_blind: usize, seed: Option<u64>) -> Self {
+        assert!((MIN_SEATS..=MAX_SEATS).contains(&players.len()), "A table needs between {MIN_SEATS} and {MAX_SEATS} seats");
+
+        let num_players = players.len();
+
+        Table {
+            deck: Deck::new(),
+            state: GameState::MainMenu(false),
+            players,
+            board: vec!(),
+            dealer: 0,
+            small_blind,
+            big_blind,
+            ante: 0,
+            big_blind_ante: false,
+            dead_money: 0,
+            schedule: None,
+            cash: None,
+            current_bet: 0,
+            last_raise: 0,
+            full_bet: 0,
+            street_bet: 0,
+            showdown: None,
+            run_it_twice: false,
+            straddle: false,
+            bomb_pot_every: 0,
+            bomb_pot_ante: 0,
+            hand_number: 0,
+            straddler: None,
+            second_board: None,
+            runout_start: 0,
+            busted: vec!(),
+            rng: seed.map_or_else(StdRng::from_os_rng, StdRng::seed_from_u64),
+            events: vec!(),
+            acted: vec![None; num_players]
+        }
+    }
+
+    pub fn from_config(config: &TableConfig) -> Self {
+        let players = config.seats.iter()
+            .map(|s| Player::new(s.name.clone(), config.stack, s.actor.create()))
+            .collect();
+
+        let mut table = Table::new(players, config.small_blind, config.big_blind, config.seed);
+        table.ante = config.ante;
+        table.big_blind_ante = config.big_blind_ante;
+        table.run_it_twice = config.run_it_twice;
+        table.straddle = config.straddle;
+        table.bomb_pot_every = config.bomb_pot_every;
+        table.bomb_pot_ante = config.bomb_pot_ante;
+
+        if !config.levels.is_empty() {
+            table.schedule = Some(BlindSchedule::new(config.levels.clone()));
+            table.apply_level();
+        }
+
+        if config.cash_game {
+            table.cash = Some(CashGame::new(config.stack, config.seats.iter().map(|s| s.actor).collect()));
+        }
+
+        table
+    }
+
+    pub fn from_saved(config: &TableConfig, saved: SavedTable) -> Self {
+        let mut table = Table::from_config(config);
+        table.events.clear();
+
+        table.state = saved.state;
+        table.rng = StdRng::seed_from_u64(saved.seed);
+        table.dealer = saved.dealer;
+        table.small_blind = saved.small_blind;
+        table.big_blind = saved.big_blind;
+        table.ante = saved.ante;
+        table.dead_money = saved.dead_money;
+        table.current_bet = saved.current_bet;
+        table.last_raise = saved.last_raise;
+        table.full_bet = saved.full_bet;
+        table.street_bet = saved.street_bet;
+        table.deck.cards = saved.deck;
+        table.board = saved.board;
+        table.busted = saved.busted;
+        table.hand_number = saved.hand_number;
+        table.straddler = saved.straddler;
+        table.second_board = saved.second_board;
+        table.runout_start = saved.runout_start;
+
+        for (seat, player) in saved.players.into_iter().enumerate() {
+            table.players[seat].money = player.money;
+            table.players[seat].bet = player.bet;
+            table.players[seat].ante = player.ante;
+            table.players[seat].folded = player.folded;
+            table.players[seat].sitting_out = player.sitting_out;
+            table.players[seat].bought_in = player.bought_in.unwrap_or(config.stack);
+            table.players[seat].hand = player.hand;
+            table.acted[seat] = player.acted;
+
+            if let Some(name) = player.name {
+                table.players[seat].name = name;
+            }
+        }
+
+        if let (Some(schedule), Some(saved)) = (&mut table.schedule, saved.schedule) {
+            schedule.level = saved.level.min(schedule.levels.len() - 1);
+            schedule.hands_played = saved.hands_played;
+            schedule.timer = schedule.current().timer();
+
+            if let Some(elapsed) = saved.elapsed {
+                schedule.timer.resume(elapsed);
+            }
+        }
+
+        if let (Some(cash), Some(saved)) = (&mut table.cash, saved.cash) {
+            cash.next_opponent = saved.next_opponent;
+        }
+
+        table.showdown = saved.showdown.map(|showdown| Showdown {
+            plays: table.plays_on(table.last_board()),
+            winners: showdown.winners.into_iter().collect(),
+            contenders: showdown.contenders.into_iter().collect()
+        });
+
+        table
+    }
+
+    pub fn save(&mut self) -> SavedTable {
+        let seed = self.rng.random();
+        self.rng = StdRng::seed_from_u64(seed);
+
+        SavedTable {
+            state: self.state,
+            seed,
+            dealer: self.dealer,
+            small_blind: self.small_blind,
+            big_blind: self.big_blind,
+            ante: self.ante,
+            dead_money: self.dead_money,
+            current_bet: self.current_bet,
+            last_raise: self.last_raise,
+            full_bet: self.full_bet,
+            street_bet: self.street_bet,
+            deck: self.deck.cards.clone(),
+            board: self.board.clone(),
+            busted: self.busted.clone(),
+            hand_number: self.hand_number,
+            straddler: self.straddler,
+            second_board: self.second_board.clone(),
+            runout_start: self.runout_start,
+            players: self.players.iter().zip(&self.acted).map(|(p, acted)| SavedPlayer {
+                name: Some(p.name.clone()),
+                money: p.money,
+                bet: p.bet,
+                ante: p.ante,
+                folded: p.folded,
+                sitting_out: p.sitting_out,
+                bought_in: Some(p.bought_in),
+                hand: p.hand.clone(),
+                acted: *acted
+            }).collect(),
+            schedule: self.schedule.as_ref().map(|s| SavedSchedule {
+                level: s.level,
+                hands_played: s.hands_played,
+                elapsed: s.timer.elapsed()
+            }),
+            cash: self.cash.as_ref().map(|c| SavedCash { next_opponent: c.next_opponent }),
+            showdown: self.showdown.as_ref().map(|s| SavedShowdown {
+                winners: s.winners.iter().copied().sorted().collect(),
+                contenders: s.contenders.iter().copied().sorted().collect()
+            })
+        }
+    }
+
+    pub fn apply_level(&mut self) {
+        if let Some(schedule) = &self.schedule {
+            let level = schedule.current();
+
+            self.small_blind = level.small_blind;
+            self.big_blind = level.big_blind;
+            self.ante = level.ante;
+
+            self.emit(GameEvent::LevelStarted {
+                level: schedule.level,
+                small_blind: level.small_blind,
+                big_blind: level.big_blind,
+                ante: level.ante
+            });
+        }
+    }
+
+    pub fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn start(&mut self) {
+        self.state = GameState::Dealing;
+
+        if let Some(schedule) = &mut self.schedule {
+            schedule.start();
+        }
+    }
+
+    pub fn next_turn(&self, turn: usize) -> usize {
+        let num_players = self.players.len();
+        let mut res = (turn + 1) % num_players;
+
+        while self.players[res].lost() {
+            res = (res + 1) % num_players;
+        }
+
+        res
+    }
+
+    pub fn heads_up(&self) -> bool {
+        self.players.iter().filter(|p| !p.lost()).count() == 2
+    }
+
+    // Heads-up the button posts the small blind and acts first pre-flop
+    pub fn small_blind_seat(&self) -> usize {
+        if self.heads_up() { self.dealer } else { self.next_turn(self.dealer) }
+    }
+
+    // Seat whose action closes the street when the bets are balanced, the last blind pre-flop
+    pub fn closing_seat(&self, num_flipped: usize) -> usize {
+        if num_flipped == 0 { self.straddler.unwrap_or(self.next_turn(self.small_blind_seat())) } else { self.dealer }
+    }
+
+    pub fn only_one_left(&self) -> bool {
+        self.players.iter().filter(|p| !p.folded && !p.lost()).count() == 1
+    }
+
+    fn can_still_bet(&self, seat: usize) -> bool {
+        let player = &self.players[seat];
+
+        !player.folded && !player.lost() && !player.is_all_in()
+    }
+
+    pub fn can_act(&self, turn: usize, initial: bool) -> bool {
+        let player = &self.players[turn];
+        let facing_bet = player.bet < self.current_bet;
+
+        // Nobody is left to bet against, so only a pending call is possible
+        let opponents_can_bet = (0..self.players.len()).any(|i| i != turn && self.can_still_bet(i));
+
+        // Blinds that were not raised still get their option
+        let option = self.acted[turn].is_none();
+
+        !self.only_one_left() && self.can_still_bet(turn) && (!initial || facing_bet || option) && (facing_bet || opponents_can_bet)
+    }
+
+    pub fn can_raise(&self, turn: usize) -> bool {
+        let player = &self.players[turn];
+
+        // A short all-in does not reopen the betting for players that already acted
+        let reopened = self.acted[turn].is_none_or(|full_bet| self.full_bet > full_bet);
+        let opponents_can_bet = (0..self.players.len()).any(|i| i != turn && self.can_still_bet(i));
+
+        self.can_still_bet(turn) && player.money > self.current_bet - player.bet && reopened && opponents_can_bet
+    }
+
+    pub fn legal_actions(&self, seat: usize) -> LegalActions {
+        let player = &self.players[seat];
+
+        let current_bet = self.current_bet - self.street_bet;
+        let max_raise_to = (player.bet + player.money).saturating_sub(self.street_bet);
+
+        LegalActions {
+            current_bet,
+            call: (self.current_bet - player.bet).min(player.money),
+            opened: current_bet > 0,
+            can_raise: self.can_raise(seat),
+            min_raise_to: (current_bet + self.last_raise).min(max_raise_to),
+            max_raise_to,
+            all_in_raises: player.bet + player.money > self.current_bet,
+            pot: self.pot()
+        }
+    }
+
+    pub fn validate_action(&self, action: &Action, turn: usize) -> Result<(), ActionError> {
+        if !self.can_still_bet(turn) {
+            return Err(ActionError::CannotAct);
+        }
+
+        let legal = self.legal_actions(turn);
+
+        match *action {
+            Action::Check if legal.call > 0 => Err(ActionError::CannotCheck { call: legal.call }),
+            Action::Call if legal.call == 0 => Err(ActionError::NothingToCall),
+            Action::Bet(_) if legal.opened => Err(ActionError::AlreadyBet),
+            Action::RaiseTo(_) if !legal.opened => Err(ActionError::NoBetToRaise),
+
+            Action::Bet(raise_to) | Action::RaiseTo(raise_to) => {
+                if !legal.can_raise {
+                    Err(ActionError::RaiseNotAllowed)
+
+                } else if raise_to > legal.max_raise_to {
+                    Err(ActionError::NotEnoughChips { stack: self.players[turn].money })
+
+                } else if raise_to < legal.min_raise_to {
+                    Err(ActionError::RaiseTooSmall { min_raise_to: legal.min_raise_to })
+
+                } else {
+                    Ok(())
+                }
+            },
+
+            Action::AllIn if legal.all_in_raises && !legal.can_raise => Err(ActionError::RaiseNotAllowed),
+
+            _ => Ok(())
+        }
+    }
+
+    // Chips an action takes from the stack of the player
+    pub fn chips_for(&self, action: &Action, seat: usize) -> usize {
+        let player = &self.players[seat];
+
+        match *action {
+            Action::Fold | Action::Check => 0,
+            Action::Call => (self.current_bet - player.bet).min(player.money),
+            Action::Bet(raise_to) | Action::RaiseTo(raise_to) => self.street_bet + raise_to - player.bet,
+            Action::AllIn => player.money
+        }
+    }
+
+    fn start_street(&mut self) {
+        self.street_bet = self.current_bet;
+        self.last_raise = self.big_blind;
+        self.full_bet = self.current_bet;
+        self.acted.iter_mut().for_each(|a| *a = None);
+    }
+
+    pub fn bet(&mut self, turn: usize, chips: usize) {
+        self.players[turn].bet_chips(chips);
+
+        if self.current_bet < self.players[turn].bet {
+            self.current_bet = self.players[turn].bet;
+        }
+    }
+
+    pub fn post_blind(&mut self, turn: usize, blind: Blind) {
+        let amount = match blind {
+            Blind::Small => self.small_blind,
+            Blind::Big => self.big_blind - self.players[turn].bet,
+            Blind::Straddle => 2 * self.big_blind,
+            Blind::Ante => unreachable!("Antes are posted by post_antes")
+        };
+
+        let amount = amount.min(self.players[turn].money);
+        self.bet(turn, amount);
+
+        self.emit(GameEvent::BlindPosted { seat: turn, blind, amount });
+
+        // The big blind ante is posted after the blind, as dead money for the whole table
+        if blind == Blind::Big && self.big_blind_ante && self.ante > 0 {
+            let amount = self.ante.min(self.players[turn].money);
+
+            self.players[turn].money -= amount;
+            self.dead_money += amount;
+
+            self.emit(GameEvent::BlindPosted { seat: turn, blind: Blind::Ante, amount });
+        }
+    }
+
+    // The straddle is only offered with a small and a big blind to go before it
+    fn can_straddle(&self, seat: usize) -> bool {
+        self.straddle && !self.heads_up() && self.players[seat].money > 0
+    }
+
+    pub fn is_bomb_pot(&self) -> bool {
+        self.bomb_pot_every > 0 && self.hand_number.is_multiple_of(self.bomb_pot_every)
+    }
+
+    // Everyone puts the same ante in and the hand starts on the flop
+    fn post_bomb_pot(&mut self) {
+        self.emit(GameEvent::BombPotStarted { ante: self.bomb_pot_ante });
+
+        for seat in 0..self.players.len() {
+            if !self.players[seat].lost() {
+                let amount = self.bomb_pot_ante.min(self.players[seat].money);
+                self.players[seat].ante_chips(amount);
+
+                self.emit(GameEvent::BlindPosted { seat, blind: Blind::Ante, amount });
+            }
+        }
+
+        self.next_street(0);
+    }
+
+    pub fn post_antes(&mut self) {
+        if self.big_blind_ante || self.ante == 0 {
+            return;
+        }
+
+        for seat in 0..self.players.len() {
+            if !self.players[seat].lost() {
+                let amount = self.ante.min(self.players[seat].money);
+                self.players[seat].ante_chips(amount);
+
+                self.emit(GameEvent::BlindPosted { seat, blind: Blind::Ante, amount });
+            }
+        }
+    }
+
+    pub fn pot(&self) -> usize {
+        self.players.iter().map(Player::contribution).sum::<usize>() + self.dead_money
+    }
+
+    pub fn perform_action(&mut self, action: Action, turn: usize) -> Result<(), ActionError> {
+        self.validate_action(&action, turn)?;
+
+        let money = self.players[turn].money;
+
+        if action == Action::Fold {
+            self.players[turn].fold();
+
+        } else {
+            self.bet(turn, self.chips_for(&action, turn));
+
+            // Short all-ins add up until they make a full raise
+            let raise = self.current_bet - self.full_bet;
+
+            if raise >= self.last_raise {
+                self.last_raise = raise;
+                self.full_bet = self.current_bet;
+            }
+        }
+
+        self.acted[turn] = Some(self.full_bet);
+
+        let amount = money - self.players[turn].money;
+        self.emit(GameEvent::ActionTaken { seat: turn, action, amount });
+
+        Ok(())
+    }
+
+    pub fn solve_pots(&mut self, plays: &[Play]) -> HashSet<usize> {
+        // Players that won something
+        let mut winners = HashSet::new();
+
+        // Initial contributions (antes are layered like any other chip put in the pot)
+        let mut contributions = self.players.iter().map(Player::contribution).collect::<Vec<_>>();
+        let mut total = contributions.iter().sum::<usize>();
+        let valid_plays = plays.iter()
+            .enumerate()
+            .filter(|(i, _)| !self.players[*i].folded)
+            .filter(|(i, _)| !self.players[*i].lost())
+            .collect::<Vec<_>>();
+
+        // Reset player bets
+        self.players.iter_mut().for_each(Player::lose_bet);
+
+        // Pot winning algorithm (dead money always goes to the main pot)
+        let mut pot = 0;
+        let mut pot_contenders = None;
+        let mut dead_money = std::mem::take(&mut self.dead_money);
+
+        while total > 0 {
+            // Calculate layers
+            let mut layers = contributions.clone();
+            layers.sort();
+            layers.dedup();
+
+            // Compute incremental tiers
+            for i in (1..layers.len()).rev() {
+                layers[i] -= layers[i - 1];
+            }
+
+            // Solve layers from lowest amount to highest
+            for layer in layers {
+                // Get players of the layer (non-folded and active)
+                let mut layer_players = valid_plays.iter()
+                    .filter(|(p, _)| contributions[*p] >= layer)
+                    .collect::<Vec<_>>();
+
+                layer_players.sort_by(|a, b| a.1.cmp(b.1).then_with(|| a.0.cmp(&b.0)));
+
+                // Get tied best players of the layer
+                let (_, best_play) = layer_players.last().unwrap();
+                let tied_best_players = layer_players.iter()
+                    .filter(|(_, p)| p == best_play)
+                    .map(|i| i.0)
+                    .collect::<Vec<_>>();
+
+                // Subtract layer value from contributions to get remaining contributions
+                let mut layer_amount = 0;
+
+                for c in contributions.iter_mut() {
+                    let amount = layer.min(*c);
+                    *c -= amount;
+                    layer_amount += amount;
+                }
+
+                total -= layer_amount;
+                layer_amount += std::mem::take(&mut dead_money);
+
+                // Consecutive layers contended by the same players make up a single pot
+                let mut contenders = layer_players.iter().map(|p| p.0).collect::<Vec<_>>();
+                contenders.sort();
+
+                if layer_amount > 0 {
+                    if pot_contenders.as_ref().is_some_and(|c| *c != contenders) {
+                        pot += 1;
+                    }
+
+                    pot_contenders = Some(contenders);
+                }
+
+                // Distribute the amount got from this layer
+                let base_amount = layer_amount / tied_best_players.len();
+                let mut remainder = layer_amount % tied_best_players.len();
+
+                for p in tied_best_players.iter() {
+                    let won_amount = base_amount + 1.min(remainder);
+                    self.players[*p].win(won_amount);
+
+                    remainder = remainder.saturating_sub(1); // Remainder is distributed in seating order
+
+                    if won_amount > 0 {
+                        winners.insert(*p);
+                        self.emit(GameEvent::PotAwarded { pot, seat: *p, amount: won_amount });
+                    }
+                }
+
+            }
+        }
+
+        winners
+    }
+
+    // The part of the biggest contribution nobody matched goes back to its owner before any pot is played
+    fn return_uncalled_bet(&mut self) {
+        let contributions = self.players.iter().map(Player::contribution).collect::<Vec<_>>();
+        let Some((seat, most)) = contributions.iter().copied().enumerate().max_by_key(|(_, c)| *c) else {
+            return;
+        };
+
+        let called = contributions.iter().enumerate()
+            .filter(|(i, _)| *i != seat)
+            .map(|(_, c)| *c)
+            .max()
+            .unwrap_or(0);
+
+        let amount = most - called;
+
+        if amount == 0 {
+            return;
+        }
+
+        let player = &mut self.players[seat];
+        let from_bet = amount.min(player.bet);
+
+        player.bet -= from_bet;
+        player.ante -= amount - from_bet;
+        player.win(amount);
+
+        self.emit(GameEvent::UncalledBetReturned { seat, amount });
+    }
+
+    // Board the hand is decided on, the second one when it is run twice
+    pub fn last_board(&self) -> &[Card] {
+        self.second_board.as_deref().unwrap_or(&self.board)
+    }
+
+    pub fn board_cards(&self, board: usize) -> &[Card] {
+        if board == 0 { &self.board } else { self.last_board() }
+    }
+
+    fn plays_on(&self, board: &[Card]) -> Vec<Play> {
+        self.players.iter().map(|p| analyze_play(&p.hand, board)).collect()
+    }
+
+    pub fn contenders(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.players.len()).filter(|i| !self.players[*i].folded && !self.players[*i].lost())
+    }
+
+    // Nobody can bet anymore, the remaining cards are just dealt
+    fn betting_over(&self) -> bool {
+        (0..self.players.len()).filter(|i| self.can_still_bet(*i)).count() <= 1
+    }
+
+    fn next_street(&mut self, num_flipped: usize) {
+        if self.only_one_left() {
+            // The hand is won without the rest of the board, its cards go back to the deck
+            let undealt = self.board.split_off(num_flipped);
+            self.deck.cards.extend(undealt);
+
+            self.showdown();
+
+        } else if num_flipped < 5 && self.betting_over() {
+            self.start_runout(num_flipped);
+
+        } else if num_flipped < 5 {
+            let next_flipped = next_flipped(num_flipped);
+
+            self.state = GameState::Round(next_flipped, self.next_turn(self.dealer), true, true, false);
+            self.start_street();
+
+            let cards = self.board[num_flipped..next_flipped].to_vec();
+            self.emit(GameEvent::StreetDealt { board: 0, street: Street::from_flipped(next_flipped), cards });
+
+        } else {
+            self.showdown();
+        }
+    }
+
+    fn start_runout(&mut self, num_flipped: usize) {
+        let hands = self.contenders().map(|i| (i, self.players[i].hand.clone())).collect();
+
+        if self.run_it_twice {
+            let mut second_board = self.board[..num_flipped].to_vec();
+
+            while second_board.len() < 5 {
+                second_board.push(self.deck.pop().expect("No more cards"));
+            }
+
+            self.second_board = Some(second_board);
+        }
+
+        self.runout_start = num_flipped;
+        self.emit(GameEvent::RunoutStarted { hands, boards: 1 + self.second_board.is_some() as usize });
+
+        self.state = GameState::Runout(num_flipped, 0);
+    }
+
+    fn showdown(&mut self) {
+        let contenders = self.contenders().collect();
+        let stacks = self.players.iter().map(|p| p.money + p.contribution()).collect::<Vec<_>>();
+
+        self.return_uncalled_bet();
+
+        let mut plays = self.plays_on(&self.board);
+        let hands = self.contenders().map(|i| (i, plays[i].clone())).collect();
+
+        self.emit(GameEvent::Showdown { board: 0, hands });
+
+        let mut winners = HashSet::new();
+
+        // Each board is played for half of every contribution, the odd chips go to the first one
+        if let Some(second_board) = self.second_board.clone() {
+            let halves = self.players.iter().map(|p| (p.bet / 2, p.ante / 2)).collect::<Vec<_>>();
+            let dead_half = self.dead_money / 2;
+
+            for (player, (bet, ante)) in self.players.iter_mut().zip(&halves) {
+                player.bet -= bet;
+                player.ante -= ante;
+            }
+
+            self.dead_money -= dead_half;
+            winners = self.solve_pots(&plays);
+
+            for (player, (bet, ante)) in self.players.iter_mut().zip(&halves) {
+                player.bet = *bet;
+                player.ante = *ante;
+            }
+
+            self.dead_money = dead_half;
+
+            plays = self.plays_on(&second_board);
+            let hands = self.contenders().map(|i| (i, plays[i].clone())).collect();
+
+            self.emit(GameEvent::Showdown { board: 1, hands });
+        }
+
+        winners.extend(self.solve_pots(&plays));
+
+        // Players busted in the same hand are placed by the stack they started it with
+        let eliminated = (0..self.players.len())
+            .filter(|s| !self.players[*s].hand.is_empty() && self.players[*s].lost())
+            .sorted_by_key(|s| stacks[*s])
+            .collect::<Vec<_>>();
+
+        for seat in eliminated {
+            if self.cash.is_none() {
+                self.busted.push(seat);
+            }
+
+            self.emit(GameEvent::PlayerEliminated { seat });
+        }
+
+        self.emit(GameEvent::HandFinished);
+
+        self.showdown = Some(Showdown { plays, winners, contenders });
+        self.state = GameState::Resolving;
+    }
+
+    pub fn collect_cards(&mut self) {
+        // Only the cards of the second board that were not shared come from the deck
+        if let Some(mut second_board) = self.second_board.take() {
+            self.deck.cards.extend(second_board.drain(self.runout_start..));
+        }
+
+        for p in &mut self.players {
+            self.deck.cards.append(&mut p.hand);
+        }
+
+        self.deck.cards.append(&mut self.board);
+        self.deck.shuffle(&mut self.rng);
+    }
+
+    // Seats from the first place to the last one, the players still in are placed by their chips
+    pub fn standings(&self) -> Vec<usize> {
+        (0..self.players.len())
+            .filter(|s| !self.busted.contains(s))
+            .sorted_by_key(|s| std::cmp::Reverse(self.players[*s].money))
+            .chain(self.busted.iter().rev().copied())
+            .collect()
+    }
+
+    // Players that will be dealt the next hand, counting the seats that get their chips back
+    pub fn players_for_next_hand(&self) -> usize {
+        self.players.iter().enumerate()
+            .filter(|(_, p)| !p.sitting_out)
+            .filter(|(i, p)| p.money > 0 || self.cash.as_ref().is_some_and(|c| c.refills(*i)))
+            .count()
+    }
+
+    pub fn can_continue(&self) -> bool {
+        self.cash.is_none() || self.players_for_next_hand() > 1
+    }
+
+    // Only between hands, the stack is brought back to the buy-in
+    pub fn top_up(&mut self, seat: usize) -> Result<(), String> {
+        let Some(cash) = &self.cash else {
+            return Err("Chips can only be added in cash games".into());
+        };
+
+        let amount = cash.buy_in.saturating_sub(self.players[seat].money);
+
+        if amount == 0 {
+            return Err(format!("Your stack is already at the buy-in of {}", cash.buy_in));
+        }
+
+        self.players[seat].add_chips(amount);
+        self.emit(GameEvent::ChipsAdded { seat, amount });
+
+        Ok(())
+    }
+
+    pub fn set_sitting_out(&mut self, seat: usize, sitting_out: bool) -> Result<(), String> {
+        if self.cash.is_none() {
+            return Err("Sitting out is only possible in cash games".into());
+        }
+
+        if !sitting_out && self.players[seat].money == 0 {
+            return Err("Top up before sitting back in".into());
+        }
+
+        self.players[seat].sitting_out = sitting_out;
+
+        // Somebody has to be left to play against
+        if sitting_out && self.players_for_next_hand() < 2 {
+            self.players[seat].sitting_out = false;
+            return Err("There are not enough players to keep the game going".into());
+        }
+
+        self.emit(if sitting_out { GameEvent::SatOut { seat } } else { GameEvent::SatIn { seat } });
+
+        Ok(())
+    }
+
+    // Busted AI seats buy chips again or are taken by a new opponent
+    fn refill_seats(&mut self) {
+        let Some(cash) = &mut self.cash else {
+            return;
+        };
+
+        for seat in 0..self.players.len() {
+            if self.players[seat].money > 0 || !cash.refills(seat) {
+                continue;
+            }
+
+            if self.rng.random_bool(0.5) {
+                let name = cash.new_opponent();
+
+                self.deck.cards.append(&mut self.players[seat].hand);
+                self.players[seat] = Player::new(name.clone(), 0, cash.actors[seat].create());
+                self.events.push(GameEvent::PlayerJoined { seat, name });
+            }
+
+            self.players[seat].add_chips(cash.buy_in);
+            self.events.push(GameEvent::ChipsAdded { seat, amount: cash.buy_in });
+        }
+    }
+
+    pub fn finish_hand(&mut self) {
+        self.refill_seats();
+
+        let finished = self.cash.is_none() && self.players.iter().filter(|p| !p.lost()).count() == 1;
+
+        self.showdown = None;
+
+        if finished {
+            // Take cards back to deck
+            self.collect_cards();
+
+            // State transition
+            let won = !self.players[0].lost();
+            self.state = GameState::End(won);
+
+        } else {
+            self.state = GameState::Collecting;
+            self.dealer = self.next_turn(self.dealer);
+            self.current_bet = 0;
+        }
+    }
+
+    pub fn update(&mut self, controls: &mut Controls) {
+        match self.state {
+            GameState::MainMenu(_) |
+            GameState::Resolving |
+            GameState::End(_) => {}, // Wait for the front end
+
+            GameState::Dealing => {
+                // Move to the next blind level between hands
+                if self.schedule.as_mut().is_some_and(BlindSchedule::next_hand) {
+                    self.apply_level();
+                }
+
+                // Prepare players
+                self.players.iter_mut().for_each(Player::unfold);
+                self.start_street();
+                self.hand_number += 1;
+                self.straddler = None;
+
+                // Prepare cards
+                self.deck.shuffle(&mut self.rng);
+
+                // Seats that are out of the hand, busted or sitting out, have no stack in it
+                let stacks = self.players.iter().map(|p| if p.lost() { 0 } else { p.money }).collect();
+                self.emit(GameEvent::HandStarted { dealer: self.dealer, small_blind: self.small_blind, big_blind: self.big_blind, stacks });
+
+                for seat in 0..self.players.len() {
+                    if !self.players[seat].lost() {
+                        for _ in 0..2 {
+                            self.players[seat].give_card(self.deck.pop().expect("No more cards"));
+                        }
+
+                        let cards = self.players[seat].hand.clone();
+                        self.emit(GameEvent::HoleCardsDealt { seat, cards });
+                    }
+                }
+
+                for _ in 0..5 {
+                    self.board.push(self.deck.pop().expect("No more cards"));
+                }
+
+                if self.is_bomb_pot() {
+                    self.post_bomb_pot();
+
+                } else {
+                    self.post_antes();
+                    self.state = GameState::Round(0, self.small_blind_seat(), false, false, false);
+                }
+            },
+
+            GameState::Straddle(turn) => {
+                let Some(straddle) = self.players[turn].actor.straddle(controls, &mut self.rng) else {
+                    return; // Wait for the actor to decide
+                };
+
+                // The straddle acts as a third blind, so its player acts last pre-flop
+                if straddle {
+                    self.post_blind(turn, Blind::Straddle);
+                    self.straddler = Some(turn);
+
+                    self.last_raise = self.current_bet.max(self.big_blind);
+                    self.full_bet = self.current_bet;
+                    self.state = GameState::Round(0, self.next_turn(turn), true, true, true);
+
+                } else {
+                    self.state = GameState::Round(0, turn, true, true, true);
+                }
+            },
+
+            GameState::Round(num_flipped, turn, sb, bb, mut initial) => {
+                if !self.players[turn].actor.turn_started() {
+                    self.players[turn].actor.start_turn();
+                }
+
+                // Information for the actors to decide
+                let actor_info = ActorInfo {
+                    player: turn,
+                    legal: self.legal_actions(turn),
+                    pot: self.pot(),
+                    hand: self.players[turn].hand.clone(),
+                    community: self.board[..num_flipped].to_vec(),
+                    players: self.players.iter().enumerate()
+                        .filter(|p| !p.1.lost())
+                        .map(|(i, p)| (i, (p.money, p.bet, p.folded)))
+                        .collect(),
+                };
+
+                if !sb && !bb { // Small blind
+                    if self.players[turn].actor.done(true, controls, actor_info, &mut self.rng) {
+                        self.post_blind(turn, Blind::Small);
+                        self.players[turn].actor.end_turn();
+
+                        self.state = GameState::Round(num_flipped, self.next_turn(turn), true, bb, true);
+                    }
+
+                } else if sb && !bb { // Big blind
+                    if self.players[turn].actor.done(true, controls, actor_info, &mut self.rng) {
+                        self.post_blind(turn, Blind::Big);
+                        self.players[turn].actor.end_turn();
+
+                        let next = self.next_turn(turn);
+
+                        self.state = if self.can_straddle(next) {
+                            GameState::Straddle(next)
+                        } else {
+                            GameState::Round(num_flipped, next, true, true, true)
+                        };
+
+                        self.last_raise = self.big_blind;
+                        self.full_bet = self.current_bet;
+                    }
+
+                } else {
+                    // Normal turn
+                    let only_one_left = self.only_one_left();
+
+                    if self.can_act(turn, initial) {
+                        if self.players[turn].actor.done(false, controls, actor_info, &mut self.rng) {
+                            let action = self.players[turn].actor.get_action();
+                            let current_bet = self.current_bet;
+
+                            if let Err(error) = self.perform_action(action.clone(), turn) {
+                                self.players[turn].actor.end_turn();
+                                self.emit(GameEvent::ActionRejected { seat: turn, action, error });
+
+                                return; // Ask the actor again
+                            }
+
+                            if self.current_bet > current_bet {
+                                initial = true;
+                            }
+
+                            self.players[turn].actor.end_turn();
+
+                        } else {
+                            return; // Wait for the actor to be done
+                        }
+
+                    } else {
+                        // End turn inmediately if no action is possible
+                        self.players[turn].actor.end_turn();
+                    }
+
+                    let balanced_bet = self.players.iter()
+                        .filter(|p| !p.folded)
+                        .filter(|p| !p.is_all_in())
+                        .filter(|p| !p.lost())
+                        .all(|i| i.bet == self.current_bet);
+
+                    // Pass stage
+                    if turn == self.closing_seat(num_flipped) && (balanced_bet || only_one_left) {
+                        self.next_street(num_flipped);
+
+                    } else {
+                        self.state = GameState::Round(num_flipped, self.next_turn(turn), sb, bb, initial);
+                    }
+                }
+            },
+
+            GameState::Runout(num_flipped, board) => {
+                if num_flipped < 5 {
+                    let next_flipped = next_flipped(num_flipped);
+                    let cards = self.board_cards(board)[num_flipped..next_flipped].to_vec();
+
+                    self.emit(GameEvent::StreetDealt { board, street: Street::from_flipped(next_flipped), cards });
+                    self.state = GameState::Runout(next_flipped, board);
+
+                } else if board == 0 && self.second_board.is_some() {
+                    self.state = GameState::Runout(self.runout_start, 1);
+
+                } else {
+                    self.showdown();
+                }
+            },
+
+            GameState::Collecting => {
+                self.collect_cards();
+                self.state = GameState::Dealing;
+            }
+        };
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::actor::{actor::PokerActor, adhoc::AdHocActor};
+
+    // Plays the given actions in order, then checks or calls
+    struct ScriptedActor {
+        actions: VecDeque<Action>,
+        selected: Option<Action>,
+        started: bool,
+        straddles: bool
+    }
+
+    impl ScriptedActor {
+        fn new(actions: Vec<Action>) -> Self {
+            ScriptedActor { actions: actions.into(), selected: None, started: false, straddles: false }
+        }
+    }
+
+    impl PokerActor for ScriptedActor {
+        fn start_turn(&mut self) {
+            self.started = true;
+        }
+
+        fn turn_started(&self) -> bool {
+            self.started
+        }
+
+        fn done(&mut self, forced: bool, _controls: &mut Controls, info: ActorInfo, _rng: &mut StdRng) -> bool {
+            if !forced {
+                self.selected = Some(self.actions.pop_front().unwrap_or(info.legal.call_or_check()));
+            }
+
+            true
+        }
+
+        fn get_action(&mut self) -> Action {
+            self.selected.take().unwrap()
+        }
+
+        fn end_turn(&mut self) {
+            self.started = false;
+            self.selected = None;
+        }
+
+        fn straddle(&mut self, _controls: &mut Controls, _rng: &mut StdRng) -> Option<bool> {
+            Some(self.straddles)
+        }
+    }
+
+    // The AI without the time it takes to look like it is thinking
+    struct InstantAi(AdHocActor);
+
+    impl PokerActor for InstantAi {
+        fn start_turn(&mut self) {
+            self.0.start_turn();
+        }
+
+        fn turn_started(&self) -> bool {
+            self.0.turn_started()
+        }
+
+        fn done(&mut self, forced: bool, controls: &mut Controls, info: ActorInfo, rng: &mut StdRng) -> bool {
+            self.0.done(forced, controls, info, rng);
+            true
+        }
+
+        fn get_action(&mut self) -> Action {
+            self.0.get_action()
+        }
+
+        fn end_turn(&mut self) {
+            self.0.end_turn();
+        }
+    }
+
+    fn table(stacks: &[usize]) -> Table {
+        let players = stacks.iter().enumerate()
+            .map(|(i, stack)| Player::new(format!("Player {}", i + 1), *stack, Box::new(ScriptedActor::new(vec!()))))
+            .collect();
+
+        Table::new(players, 5, 10, Some(7))
+    }
+
+    fn cards(names: &str) -> Vec<Card> {
+        names.split_whitespace().map(|name| Card::from_short_name(name).unwrap()).collect()
+    }
+
+    // Deals a hand and posts the blinds, up to the first decision of the pre-flop
+    fn deal(table: &mut Table) {
+        let mut controls = Controls::new();
+        table.start();
+
+        while !matches!(table.state, GameState::Round(0, _, true, true, _)) {
+            table.update(&mut controls);
+        }
+    }
+
+    // Plays the rest of the hand with the given actions for each seat
+    fn play(table: &mut Table, scripts: Vec<Vec<Action>>) {
+        let mut controls = Controls::new();
+
+        for (player, actions) in table.players.iter_mut().zip(scripts) {
+            player.actor = Box::new(ScriptedActor::new(actions));
+        }
+
+        for _ in 0..1000 {
+            if table.state == GameState::Resolving {
+                return;
+            }
+
+            table.update(&mut controls);
+        }
+
+        panic!("The hand did not finish");
+    }
+
+    // Every seat gets a hand and the board is set, so that showdown() decides it
+    fn set_cards(table: &mut Table, hands: &[&str], board: &str) {
+        for (player, hand) in table.players.iter_mut().zip(hands) {
+            player.hand = cards(hand);
+        }
+
+        table.board = cards(board);
+    }
+
+    fn set_contribution(table: &mut Table, seat: usize, bet: usize, ante: usize, money: usize) {
+        let player = &mut table.players[seat];
+
+        player.bet = bet;
+        player.ante = ante;
+        player.money = money;
+    }
+
+    fn returned(events: &[GameEvent]) -> Vec<(usize, usize)> {
+        events.iter().filter_map(|e| match e {
+            GameEvent::UncalledBetReturned { seat, amount } => Some((*seat, *amount)),
+            _ => None
+        }).collect()
+    }
+
+    fn awarded(events: &[GameEvent]) -> Vec<(usize, usize, usize)> {
+        events.iter().filter_map(|e| match e {
+            GameEvent::PotAwarded { pot, seat, amount } => Some((*pot, *seat, *amount)),
+            _ => None
+        }).collect()
+    }
+
+    #[test]
+    fn short_all_in_does_not_reopen_betting() {
+        let mut table = table(&[1000, 40, 1000]);
+        deal(&mut table);
+
+        // Seat 0 is under the gun, then the small and the big blind
+        table.perform_action(Action::RaiseTo(30), 0).unwrap();
+        table.perform_action(Action::AllIn, 1).unwrap();
+
+        // The big blind has not acted yet, so it may still raise
+        assert!(table.can_raise(2));
+        table.perform_action(Action::Call, 2).unwrap();
+
+        // The all-in to 40 is 10 more than the raise to 30, short of a full raise of 20
+        assert!(!table.can_raise(0));
+        assert_eq!(table.validate_action(&Action::RaiseTo(100), 0), Err(ActionError::RaiseNotAllowed));
+        assert_eq!(table.validate_action(&Action::Call, 0), Ok(()));
+    }
+
+    #[test]
+    fn incomplete_raises_add_up_to_a_full_raise() {
+        let mut table = table(&[15, 20, 1000, 1000]);
+        deal(&mut table);
+
+        // Seat 3 is under the gun and limps
+        table.perform_action(Action::Call, 3).unwrap();
+        table.perform_action(Action::AllIn, 0).unwrap();
+
+        // 5 over the big blind is not a full raise
+        assert_eq!(table.current_bet, 15);
+        assert!(!table.can_raise(3));
+
+        // 10 over the big blind is, counting the short all-in before it
+        table.perform_action(Action::AllIn, 1).unwrap();
+
+        assert_eq!(table.current_bet, 20);
+        assert!(table.can_raise(3));
+        assert_eq!(table.legal_actions(3).min_raise_to, 30);
+    }
+
+    #[test]
+    fn uncalled_bet_is_returned() {
+        let mut table = table(&[1000, 100, 1000]);
+        deal(&mut table);
+
+        table.perform_action(Action::RaiseTo(300), 0).unwrap();
+        table.perform_action(Action::AllIn, 1).unwrap();
+        table.perform_action(Action::Fold, 2).unwrap();
+
+        set_cards(&mut table, &["Kh Ks", "Ah As", "2d 3d"], "2c 7d 9h Jc 4s");
+        table.drain_events();
+        table.showdown();
+
+        let events = table.drain_events();
+
+        // Only 100 of the raise to 300 were called
+        assert_eq!(returned(&events), vec!((0, 200)));
+        assert_eq!(awarded(&events), vec!((0, 1, 30), (0, 1, 180)));
+        assert_eq!(table.players.iter().map(|p| p.money).collect::<Vec<_>>(), vec!(900, 210, 990));
+    }
+
+    #[test]
+    fn antes_are_layered_into_side_pots() {
+        let mut table = table(&[1000, 1000, 1000]);
+
+        set_contribution(&mut table, 0, 45, 5, 0);
+        set_contribution(&mut table, 1, 200, 5, 795);
+        set_contribution(&mut table, 2, 200, 5, 795);
+
+        set_cards(&mut table, &["Ah As", "Kh Ks", "Qh Qs"], "2c 7d 9h Jc 4s");
+        table.showdown();
+
+        let events = table.drain_events();
+
+        // The short stack wins what everyone matched of its 50, antes included
+        assert_eq!(awarded(&events), vec!((0, 0, 150), (1, 1, 310)));
+        assert_eq!(table.players.iter().map(|p| p.money).collect::<Vec<_>>(), vec!(150, 1105, 795));
+    }
+
+    #[test]
+    fn big_blind_ante_is_dead_money_in_the_main_pot() {
+        let mut table = table(&[1000, 1000, 1000]);
+        table.ante = 5;
+        table.big_blind_ante = true;
+        deal(&mut table);
+
+        // The big blind pays the ante of the whole table, nobody else does
+        assert_eq!(table.dead_money, 5);
+        assert_eq!(table.players.iter().map(|p| p.money).collect::<Vec<_>>(), vec!(1000, 995, 985));
+        assert!(table.players.iter().all(|p| p.ante == 0));
+
+        set_contribution(&mut table, 0, 20, 0, 0);
+        set_contribution(&mut table, 1, 100, 0, 900);
+        set_contribution(&mut table, 2, 100, 0, 885);
+
+        set_cards(&mut table, &["Ah As", "Kh Ks", "Qh Qs"], "2c 7d 9h Jc 4s");
+        table.showdown();
+
+        let events = table.drain_events();
+
+        assert_eq!(awarded(&events), vec!((0, 0, 65), (1, 1, 160)));
+        assert_eq!(table.dead_money, 0);
+    }
+
+    #[test]
+    fn heads_up_button_posts_small_blind_and_acts_first() {
+        let mut table = table(&[1000, 1000]);
+        deal(&mut table);
+
+        assert_eq!(table.small_blind_seat(), 0);
+        assert_eq!(table.closing_seat(0), 1);
+        assert_eq!(table.state, GameState::Round(0, 0, true, true, true));
+        assert_eq!((table.players[0].bet, table.players[1].bet), (5, 10));
+
+        // After the flop the big blind acts first
+        let mut controls = Controls::new();
+        table.players[0].actor = Box::new(ScriptedActor::new(vec!(Action::Call)));
+        table.players[1].actor = Box::new(ScriptedActor::new(vec!(Action::Check)));
+
+        while !matches!(table.state, GameState::Round(3, ..)) {
+            table.update(&mut controls);
+        }
+
+        assert!(matches!(table.state, GameState::Round(3, 1, ..)));
+    }
+
+    #[test]
+    fn each_board_of_a_run_twice_pot_gets_half() {
+        let mut table = table(&[101, 101]);
+        table.run_it_twice = true;
+
+        set_contribution(&mut table, 0, 101, 0, 0);
+        set_contribution(&mut table, 1, 101, 0, 0);
+
+        set_cards(&mut table, &["Ah As", "Kh Ks"], "2c 7d 9h Jc 4s");
+        table.second_board = Some(cards("Kd 2h 7c 9s 4d"));
+        table.showdown();
+
+        // The odd chips go to the first board
+        assert_eq!(table.players[0].money, 102);
+        assert_eq!(table.players[1].money, 100);
+        assert_eq!(table.pot(), 0);
+    }
+
+    #[test]
+    fn run_twice_returns_the_uncalled_bet_once_and_keeps_the_winners_of_both_boards() {
+        let mut table = table(&[300, 100]);
+        table.run_it_twice = true;
+
+        set_contribution(&mut table, 0, 300, 0, 0);
+        set_contribution(&mut table, 1, 100, 0, 0);
+
+        set_cards(&mut table, &["Ah As", "Kh Ks"], "2c 7d 9h Jc 4s");
+        table.second_board = Some(cards("Kd 2h 7c 9s 4d"));
+        table.drain_events();
+        table.showdown();
+
+        let events = table.drain_events();
+
+        assert_eq!(returned(&events), vec!((0, 200)));
+        assert_eq!(table.players.iter().map(|p| p.money).collect::<Vec<_>>(), vec!(300, 100));
+        assert_eq!(table.showdown.unwrap().winners, HashSet::from([0, 1]));
+    }
+
+    #[test]
+    fn same_seed_and_actions_play_the_same_hands() {
+        let play_hands = || {
+            let mut table = table(&[1000, 1000, 1000, 1000]);
+            let mut controls = Controls::new();
+            let mut events = vec!();
+
+            table.players[0].actor = Box::new(ScriptedActor::new(vec!(Action::RaiseTo(30), Action::Bet(40), Action::Fold)));
+
+            for player in &mut table.players[1..] {
+                player.actor = Box::new(InstantAi(AdHocActor::new()));
+            }
+
+            table.start();
+
+            // Hands are told apart by the order of the deck they start from
+            for _ in 0..3 {
+                events.push(format!("{:?}", table.deck.cards));
+
+                while table.state != GameState::Resolving {
+                    table.update(&mut controls);
+                }
+
+                events.extend(table.drain_events().iter().map(|e| format!("{e:?}")));
+                table.update(&mut controls);
+            }
+
+            events
+        };
+
+        let events = play_hands();
+
+        assert!(events.iter().any(|e| e.starts_with("StreetDealt")));
+        assert!(events.iter().filter(|e| e.starts_with("ActionTaken")).count() > 6);
+        assert_eq!(events, play_hands());
+    }
+
+    #[test]
+    fn straddle_acts_last_before_the_flop() {
+        let mut table = table(&[1000, 1000, 1000, 1000]);
+        table.straddle = true;
+        table.players[3].actor = Box::new(ScriptedActor { straddles: true, ..ScriptedActor::new(vec!()) });
+
+        deal(&mut table);
+
+        // The straddle is a third blind of two big blinds, the player after it acts first
+        assert_eq!(table.straddler, Some(3));
+        assert_eq!(table.current_bet, 20);
+        assert_eq!(table.state, GameState::Round(0, 0, true, true, true));
+        assert_eq!(table.closing_seat(0), 3);
+
+        table.drain_events();
+        play(&mut table, vec!(vec!(Action::Call), vec!(Action::Call), vec!(Action::Call), vec!(Action::Check)));
+
+        let events = table.drain_events();
+        let flop = events.iter().position(|e| matches!(e, GameEvent::StreetDealt { .. })).unwrap();
+        let pre_flop = events[..flop].iter().filter_map(|e| match e {
+            GameEvent::ActionTaken { seat, action, .. } => Some((*seat, action.clone())),
+            _ => None
+        }).collect::<Vec<_>>();
+
+        assert_eq!(pre_flop, vec!((0, Action::Call), (1, Action::Call), (2, Action::Call), (3, Action::Check)));
+    }
+
+    #[test]
+    fn bomb_pot_starts_on_the_flop() {
+        let mut table = table(&[1000, 1000, 1000]);
+        let mut controls = Controls::new();
+
+        table.bomb_pot_every = 1;
+        table.bomb_pot_ante = 10;
+        table.start();
+
+        while !matches!(table.state, GameState::Round(..)) {
+            table.update(&mut controls);
+        }
+
+        let events = table.drain_events();
+        let blinds = events.iter().filter_map(|e| match e {
+            GameEvent::BlindPosted { seat, blind, amount } => Some((*seat, *blind, *amount)),
+            _ => None
+        }).collect::<Vec<_>>();
+
+        // Everyone antes, nobody posts blinds, and the flop is out before anyone acts
+        assert!(events.iter().any(|e| matches!(e, GameEvent::BombPotStarted { ante: 10 })));
+        assert_eq!(blinds, vec!((0, Blind::Ante, 10), (1, Blind::Ante, 10), (2, Blind::Ante, 10)));
+        assert!(events.iter().any(|e| matches!(e, GameEvent::StreetDealt { street: Street::Flop, .. })));
+
+        assert!(matches!(table.state, GameState::Round(3, 1, ..)));
+        assert_eq!(table.pot(), 30);
+        assert_eq!(table.current_bet, 0);
+    }
+
+    #[test]
+    fn hand_ends_when_everybody_else_folds() {
+        let mut table = table(&[1000, 1000, 1000]);
+        deal(&mut table);
+        table.drain_events();
+
+        play(&mut table, vec!(vec!(Action::RaiseTo(30)), vec!(Action::Fold), vec!(Action::Fold)));
+
+        let events = table.drain_events();
+
+        // The board is never dealt and goes back to the deck
+        assert!(!events.iter().any(|e| matches!(e, GameEvent::StreetDealt { .. })));
+        assert!(table.board.is_empty());
+        assert_eq!(table.deck.cards.len(), 52 - 6);
+        assert_eq!(table.players.iter().map(|p| p.money).collect::<Vec<_>>(), vec!(1015, 995, 990));
+    }
+}
@@ -0,0 +1,139 @@
+use std::{fs, path::Path};
+
+use crossterm::style::Color;
+use serde::{Deserialize, Deserializer};
+
+use crate::poker::card::Suit;
+
+pub const DEFAULT_THEME: &str = "classic";
+
+// Colors of everything drawn, themes in files only need the ones that differ from the classic theme
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    #[serde(deserialize_with = "color")] pub felt: Color,
+    #[serde(deserialize_with = "color")] pub text: Color, // Text written over the felt
+    #[serde(deserialize_with = "color")] pub card_face: Color,
+    #[serde(deserialize_with = "color")] pub card_back: Color,
+    #[serde(deserialize_with = "color")] pub hearts: Color,
+    #[serde(deserialize_with = "color")] pub diamonds: Color,
+    #[serde(deserialize_with = "color")] pub clubs: Color,
+    #[serde(deserialize_with = "color")] pub spades: Color,
+    #[serde(deserialize_with = "color")] pub chips: Color, // Chip and bet labels
+    #[serde(deserialize_with = "color")] pub chips_text: Color,
+    #[serde(deserialize_with = "color")] pub label: Color, // Messages, hands and the dealer button
+    #[serde(deserialize_with = "color")] pub label_text: Color,
+    #[serde(deserialize_with = "color")] pub turn: Color,
+    #[serde(deserialize_with = "color")] pub turn_text: Color,
+    #[serde(deserialize_with = "color")] pub accent: Color, // Logo and errors
+    #[serde(deserialize_with = "color")] pub highlight: Color // The human in tables of results
+}
+
+const fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
+
+impl Theme {
+    pub fn classic() -> Self {
+        Theme {
+            felt: rgb(53, 101, 77),
+            text: Color::White,
+            card_face: Color::White,
+            card_back: Color::DarkBlue,
+            hearts: Color::Red,
+            diamonds: Color::Red,
+            clubs: Color::Black,
+            spades: Color::Black,
+            chips: rgb(227, 168, 105),
+            chips_text: Color::Black,
+            label: rgb(8, 72, 135),
+            label_text: Color::White,
+            turn: Color::Cyan,
+            turn_text: Color::Black,
+            accent: rgb(154, 49, 55),
+            highlight: Color::Yellow
+        }
+    }
+
+    pub fn four_color() -> Self {
+        Theme {
+            diamonds: rgb(0, 90, 200),
+            clubs: rgb(0, 130, 50),
+            ..Theme::classic()
+        }
+    }
+
+    // Colorblind-safe hues on black, every suit can be told apart by its color alone
+    pub fn high_contrast() -> Self {
+        Theme {
+            felt: Color::Black,
+            text: Color::White,
+            card_face: Color::White,
+            card_back: rgb(0, 114, 178),
+            hearts: rgb(213, 94, 0),
+            diamonds: rgb(0, 114, 178),
+            clubs: rgb(0, 158, 115),
+            spades: Color::Black,
+            chips: rgb(240, 228, 66),
+            chips_text: Color::Black,
+            label: Color::White,
+            label_text: Color::Black,
+            turn: rgb(230, 159, 0),
+            turn_text: Color::Black,
+            accent: rgb(213, 94, 0),
+            highlight: rgb(240, 228, 66)
+        }
+    }
+
+    // Either the name of a built-in theme or the path of a theme file
+    pub fn load(name: &str) -> Result<Self, String> {
+        match name {
+            "classic" => Ok(Theme::classic()),
+            "four-color" => Ok(Theme::four_color()),
+            "high-contrast" => Ok(Theme::high_contrast()),
+
+            path => {
+                let path = Path::new(path);
+                let content = fs::read_to_string(path).map_err(|e| format!("Unable to read theme {}: {e}", path.display()))?;
+
+                toml::from_str(&content).map_err(|e| format!("Unable to parse theme {}: {e}", path.display()))
+            }
+        }
+    }
+
+    pub fn suit(&self, suit: &Suit) -> Color {
+        match suit {
+            Suit::Hearts => self.hearts,
+            Suit::Diamonds => self.diamonds,
+            Suit::Clubs => self.clubs,
+            Suit::Spades => self.spades
+        }
+    }
+}
+
+// Colors are written as "#rrggbb" or by name, like "white" or "dark_blue"
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let value = String::deserialize(deserializer)?;
+
+    parse_color(&value).ok_or(serde::de::Error::custom(format!("invalid color: {value}")))
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let Some(hex) = value.strip_prefix('#') else {
+        return Color::try_from(value).ok();
+    };
+
+    if hex.len() != 6 {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+    Some(rgb(channel(0)?, channel(2)?, channel(4)?))
+}
@@ -19,20 +19,28 @@ impl Timer {
         self.start_time = Some(Instant::now());
     }
 
+    // Starts the timer as if it had been running for the given time
+    pub fn resume(&mut self, elapsed: Duration) {
+        self.start_time = Some(Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now));
+    }
+
     pub fn exhaust(&mut self) {
         self.start_time = None;
     }
 
     pub fn elapsed(&self) -> Option<Duration> {
-        if self.start_time.is_none() {
-            None
-        
-        } else {
-            Some(Instant::now().duration_since(self.start_time.unwrap()))
-        }
+        self.start_time.map(|t| Instant::now().duration_since(t))
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed().unwrap_or_default())
     }
 
     pub fn done(&self) -> bool {
-        self.start_time.is_none() || Instant::now().duration_since(self.start_time.unwrap()) >= self.duration
+        self.start_time.is_none_or(|t| Instant::now().duration_since(t) >= self.duration)
     }
 }
\ No newline at end of file
@@ -1,16 +1,30 @@
-use std::time::{Duration, Instant};
+use std::{path::Path, time::{Duration, Instant}};
 
 use crossterm::event::KeyCode;
 
-use crate::{engine::game::Game};
+use crate::engine::{config::TableConfig, console::flush, game::Game, theme::Theme};
 
 pub mod engine {
     pub mod console;
     pub mod controls;
     pub mod timer;
     pub mod game;
+    pub mod table;
+    pub mod event;
+    pub mod layout;
+    pub mod config;
+    pub mod blinds;
+    pub mod cash;
+    pub mod rules;
     pub mod state;
     pub mod player;
+    pub mod save;
+    pub mod history;
+    pub mod replay;
+    pub mod stats;
+    pub mod theme;
+    pub mod animation;
+    pub mod palette;
 }
 
 pub mod poker {
@@ -22,25 +36,62 @@ pub mod poker {
 
 pub mod actor {
     pub mod action;
+    #[allow(clippy::module_inception)]
     pub mod actor;
     pub mod human;
     pub mod adhoc;
 }
 
 fn main() {
-    const TARGET_FPS: u64 = 15;
-    let frame_duration: Duration = Duration::from_secs_f64(1.0 / TARGET_FPS as f64);
+    let config = match TableConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(msg) => {
+            eprintln!("{msg}");
+            std::process::exit(1);
+        }
+    };
+
+    let frame_duration: Duration = Duration::from_secs_f64(1.0 / config.fps as f64);
     
-    let mut game = Game::new();
+    let mut game = Game::new(&config);
+
+    if let Err(msg) = game.stats.load(Path::new(&config.stats_file)) {
+        eprintln!("{msg}");
+        std::process::exit(1);
+    }
+
+    game.theme = match Theme::load(&config.theme) {
+        Ok(theme) => theme,
+        Err(msg) => {
+            eprintln!("{msg}");
+            std::process::exit(1);
+        }
+    };
 
     game.startup();
 
+    let started = match &config.replay {
+        Some(path) => game.start_replay(Path::new(path)),
+        None if config.resume => game.load(),
+        None => Ok(())
+    };
+
+    if let Err(msg) = started {
+        game.finalize();
+
+        eprintln!("{msg}");
+        std::process::exit(1);
+    }
+
+    let mut saved = Ok(());
+
     loop {
         let frame_start = Instant::now();
 
         game.poll_inputs();
 
         if game.controls.is_pressed(KeyCode::Esc) {
+            saved = game.save();
             break;
         }
 
@@ -49,6 +100,7 @@ fn main() {
         }
 
         game.render();
+        flush();
 
         let frame_time = Instant::now() - frame_start;
         if frame_time < frame_duration {
@@ -57,4 +109,9 @@ fn main() {
     }
     
     game.finalize();
+
+    if let Err(msg) = saved {
+        eprintln!("{msg}");
+        std::process::exit(1);
+    }
 }
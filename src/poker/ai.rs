@@ -1,11 +1,15 @@
 use std::collections::HashSet;
 
-use rand::{rng, seq::IndexedRandom};
-use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use itertools::Itertools;
+use rand::{rngs::StdRng, seq::IndexedRandom, Rng, SeedableRng};
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 use crate::poker::{card::Card, deck::Deck, play::analyze_play};
 
-pub fn monte_carlo_likeliness_to_win(hand: &[Card], community: &[Card], num_players: usize, iters: usize) -> f32 {
+// Simulations run in a fixed number of batches, each one with its own seed, so the result only depends on the RNG
+const BATCHES: usize = 64;
+
+pub fn monte_carlo_likeliness_to_win(hand: &[Card], community: &[Card], num_players: usize, iters: usize, rng: &mut impl Rng) -> f32 {
     let unknowns = 7 - (hand.len() + community.len());
 
     let all = hand.iter().chain(community).collect::<Vec<_>>();
@@ -15,11 +19,11 @@ pub fn monte_carlo_likeliness_to_win(hand: &[Card], community: &[Card], num_play
         .filter(|a| !all.iter().any(|b| a.suit == b.suit && a.number == b.number))
         .collect::<Vec<_>>();
 
-    let equity = (0..iters).into_par_iter().map(|_| {
-        // Shuffle available cards
-        let mut rng = rng();
+    let seeds = (0..BATCHES).map(|_| rng.random()).collect::<Vec<u64>>();
 
-        let mut available_clone = available_cards.choose_multiple(&mut rng, unknowns + 2 * (num_players - 1))
+    let simulate = |rng: &mut StdRng| {
+        // Shuffle available cards
+        let mut available_clone = available_cards.choose_multiple(rng, unknowns + 2 * (num_players - 1))
             .cloned()
             .collect::<Vec<_>>();
 
@@ -55,8 +59,68 @@ pub fn monte_carlo_likeliness_to_win(hand: &[Card], community: &[Card], num_play
         } else {
             0.0
         }
+    };
+
+    // Batch results are added in order, as a parallel float sum is not reproducible
+    let batches = seeds.into_par_iter().enumerate().map(|(batch, seed)| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let batch_iters = iters / BATCHES + usize::from(batch < iters % BATCHES);
+
+        (0..batch_iters).map(|_| simulate(&mut rng)).sum::<f32>()
     })
-    .sum::<f32>();
+    .collect::<Vec<_>>();
+
+    let equity = batches.iter().sum::<f32>();
 
     equity / iters as f32
-}
\ No newline at end of file
+}
+
+fn add_shares(total: Vec<f32>, shares: Vec<f32>) -> Vec<f32> {
+    total.iter().zip(shares).map(|(a, b)| a + b).collect()
+}
+
+// Share of the pot each of the known hands wins on average over the rest of the board
+pub fn hands_equity(hands: &[Vec<Card>], community: &[Card], iters: usize, rng: &mut impl Rng) -> Vec<f32> {
+    let unknowns = 5 - community.len();
+
+    let all = hands.iter().flatten().chain(community).collect::<Vec<_>>();
+    let deck = Deck::new();
+
+    let available_cards = deck.cards.iter()
+        .filter(|a| !all.iter().any(|b| a.suit == b.suit && a.number == b.number))
+        .collect::<Vec<_>>();
+
+    let shares = |runout: &[&Card]| {
+        let board = community.iter().chain(runout.iter().copied()).cloned().collect::<Vec<_>>();
+        let plays = hands.iter().map(|h| analyze_play(h, &board)).collect::<Vec<_>>();
+
+        let best_play = plays.iter().max().unwrap();
+        let num_tied = plays.iter().filter(|p| *p == best_play).count();
+
+        plays.iter().map(|p| if p == best_play { 1.0 / num_tied as f32 } else { 0.0 }).collect::<Vec<_>>()
+    };
+
+    let zero = vec![0.0; hands.len()];
+
+    // With two cards or less to come every runout can be checked
+    if unknowns <= 2 {
+        let runouts = available_cards.iter().copied().combinations(unknowns).collect::<Vec<_>>();
+        let results = runouts.par_iter().map(|r| shares(r)).collect::<Vec<_>>();
+
+        return results.into_iter().fold(zero, add_shares).iter().map(|s| s / runouts.len() as f32).collect();
+    }
+
+    let seeds = (0..BATCHES).map(|_| rng.random()).collect::<Vec<u64>>();
+
+    let batches = seeds.into_par_iter().enumerate().map(|(batch, seed)| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let batch_iters = iters / BATCHES + usize::from(batch < iters % BATCHES);
+
+        (0..batch_iters)
+            .map(|_| shares(&available_cards.choose_multiple(&mut rng, unknowns).copied().collect::<Vec<_>>()))
+            .fold(zero.clone(), add_shares)
+    })
+    .collect::<Vec<_>>();
+
+    batches.into_iter().fold(zero, add_shares).iter().map(|s| s / iters as f32).collect()
+}
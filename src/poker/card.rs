@@ -1,22 +1,17 @@
 use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
 
-use crate::engine::console::{clear_section, move_cursor, set_color, write_str};
+use crate::engine::{console::{clear_section, is_ascii, move_cursor, set_color, write_str}, theme::Theme};
 
-pub const BAIZE: Color = Color::Rgb { r: 53, g: 101, b: 77 };
-pub const CREAM: Color = Color::Rgb { r: 227, g: 168, b: 105 };
-pub const DBLUE: Color = Color::Rgb { r: 8, g: 72, b: 135 };
-pub const DRED: Color = Color::Rgb { r: 154, g: 49, b: 55 };
-
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum Suit {
     Hearts, Diamonds, Clubs, Spades
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Card {
     pub suit: Suit,
-    pub number: usize,
-    drawn: bool
+    pub number: usize
 }
 
 impl Suit {
@@ -29,19 +24,29 @@ impl Suit {
         }
     }
 
-    pub fn color(&self) -> Color {
+    // Large symbol in the middle of an Ace
+    pub fn big_pip(&self) -> [&str; 4] {
         match self {
-            Suit::Hearts => Color::Red,
-            Suit::Diamonds => Color::Red,
-            Suit::Clubs => Color::Black,
-            Suit::Spades => Color::Black,
+            Suit::Hearts => [" ▄▄ ▄▄ ", "███████", " ▀███▀ ", "   ▀   "],
+            Suit::Diamonds => ["   ▄   ", " ▄███▄ ", " ▀███▀ ", "   ▀   "],
+            Suit::Clubs => ["  ▄█▄  ", "▄▄▀█▀▄▄", "▀▀▀█▀▀▀", "  ▄█▄  "],
+            Suit::Spades => ["   ▄   ", " ▄███▄ ", "███████", " ▀▄█▄▀ "]
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
         }
     }
 }
 
 impl Card {
     pub fn new(number: usize, suit: Suit) -> Self {
-        Card { suit, number, drawn: false }
+        Card { suit, number }
     }
 
     pub fn number_to_str(&self) -> String {
@@ -58,6 +63,39 @@ impl Card {
         format!("{}{}", self.number_to_str(), self.suit.symbol())
     }
 
+    // Two character name used in hand histories, like Ah or Td
+    pub fn short_name(&self) -> String {
+        let number = match self.number {
+            10 => "T".to_string(),
+            _ => self.number_to_str()
+        };
+
+        format!("{number}{}", self.suit.letter())
+    }
+
+    pub fn from_short_name(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+
+        let number = match chars.next()? {
+            'A' => 1,
+            'T' => 10,
+            'J' => 11,
+            'Q' => 12,
+            'K' => 13,
+            c => c.to_digit(10).filter(|n| *n >= 2)? as usize
+        };
+
+        let suit = match chars.next()? {
+            'h' => Suit::Hearts,
+            'd' => Suit::Diamonds,
+            'c' => Suit::Clubs,
+            's' => Suit::Spades,
+            _ => return None
+        };
+
+        chars.next().is_none().then(|| Card::new(number, suit))
+    }
+
     pub fn value(&self) -> usize {
         match self.number {
             1 => 14,
@@ -65,33 +103,27 @@ impl Card {
         }
     }
 
-    pub fn reset_draw_cache(&mut self) {
-        self.drawn = false;
-    }
+    pub fn draw(&self, theme: &Theme, col: usize, row: usize, back: bool) {
+        const WIDTH: usize = 10;
+        const HEIGHT: usize = 8;
 
-    pub fn draw(&mut self, col: usize, row: usize, back: bool) {
-        // Cache console
-        if self.drawn {
+        if is_ascii() {
+            self.draw_ascii(theme, col, row, back);
             return;
         }
 
-        self.drawn = true;
-
-        const WIDTH: usize = 10;
-        const HEIGHT: usize = 8;
-
         // Outer square
-        set_color(Color::White, BAIZE);
+        set_color(theme.card_face, theme.felt);
         clear_section(row, col, row + HEIGHT, col + WIDTH);
 
-        set_color(BAIZE, Color::White);
+        set_color(theme.felt, theme.card_face);
         move_cursor(row, col);
         write_str(&"▄".repeat(11));
         move_cursor(row + HEIGHT, col);
         write_str(&"▀".repeat(11));
 
         if back {
-            set_color(Color::White, Color::DarkBlue);
+            set_color(theme.card_face, theme.card_back);
 
             for r in row + 1..row + HEIGHT {
                 for c in col + 1..col + WIDTH {
@@ -112,7 +144,7 @@ impl Card {
         // Corner symbols
         let number = self.number_to_str();
 
-        set_color(Color::White, self.suit.color());
+        set_color(theme.card_face, theme.suit(&self.suit));
 
         move_cursor(row + 1, col + 1);
         write_str(&number);
@@ -132,7 +164,10 @@ impl Card {
         // Inner symbols
         match self.number {
             1 => {
-                write_suit(row + HEIGHT / 2, col + WIDTH / 2);
+                for (i, line) in self.suit.big_pip().iter().enumerate() {
+                    move_cursor(row + 2 + i, col + 2);
+                    write_str(line);
+                }
             }
 
             2 => {
@@ -216,13 +251,78 @@ impl Card {
                 write_suit(row + HEIGHT / 2 + 2, col + WIDTH / 2);
             }
 
-            11 | 12 | 13 => {
-                // Should draw something
+            11..=13 => {
+                let crown = match self.number {
+                    11 => "▄▄▀",
+                    12 => "▴▲▴",
+                    _ => "▲▲▲"
+                };
+
+                let portrait = [
+                    "╔═════╗".to_string(),
+                    format!("║{crown:^5}║"),
+                    "║ o_o ║".to_string(),
+                    format!("║╱ {} ╲║", self.suit.symbol()),
+                    "╚═════╝".to_string()
+                ];
+
+                for (i, line) in portrait.iter().enumerate() {
+                    move_cursor(row + 2 + i, col + 2);
+                    write_str(line);
+                }
+            }
+
+            // Unknown ranks only show their number
+            _ => {
                 move_cursor(row + HEIGHT / 2, col + WIDTH / 2);
                 write_str(&number);
             }
+        }
+    }
+
+    // Plain card for consoles without Unicode, like [A s] inside a +-| border
+    fn draw_ascii(&self, theme: &Theme, col: usize, row: usize, back: bool) {
+        const WIDTH: usize = 10;
+        const HEIGHT: usize = 8;
+
+        set_color(theme.card_face, Color::Black);
+        clear_section(row, col, row + HEIGHT, col + WIDTH);
+
+        for r in [row, row + HEIGHT] {
+            move_cursor(r, col);
+            write_str(&format!("+{}+", "-".repeat(WIDTH - 1)));
+        }
+
+        for r in row + 1..row + HEIGHT {
+            move_cursor(r, col);
+            write_str("|");
+            move_cursor(r, col + WIDTH);
+            write_str("|");
+        }
+
+        if back {
+            set_color(theme.card_face, theme.card_back);
+
+            for r in row + 1..row + HEIGHT {
+                move_cursor(r, col + 1);
+                write_str(&"#".repeat(WIDTH - 1));
+            }
 
-            _ => todo!()
+            return;
         }
+
+        let number = self.number_to_str();
+        let suit = self.suit.letter();
+
+        set_color(theme.card_face, theme.suit(&self.suit));
+
+        move_cursor(row + 1, col + 1);
+        write_str(&format!("{number}{suit}"));
+        move_cursor(row + HEIGHT - 1, col + WIDTH - number.len() - 1);
+        write_str(&format!("{number}{suit}"));
+
+        let center = format!("[{number} {suit}]");
+        move_cursor(row + HEIGHT / 2, col + (WIDTH + 1 - center.len()) / 2);
+        write_str(&center);
     }
-}
\ No newline at end of file
+}
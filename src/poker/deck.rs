@@ -1,4 +1,4 @@
-use rand::{rng, seq::SliceRandom};
+use rand::{seq::SliceRandom, Rng};
 
 use crate::poker::card::{Card, Suit};
 
@@ -6,6 +6,12 @@ pub struct Deck {
     pub cards: Vec<Card>
 }
 
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Deck {
     pub fn new() -> Self {
         let mut cards = vec!();
@@ -19,13 +25,8 @@ impl Deck {
         Deck { cards }
     }
 
-    pub fn reset_draw_cache(&mut self) {
-        self.cards.iter_mut().for_each(Card::reset_draw_cache);
-    }
-
-    pub fn shuffle(&mut self) {
-        let mut rng = rng();
-        self.cards.shuffle(&mut rng);
+    pub fn shuffle(&mut self, rng: &mut impl Rng) {
+        self.cards.shuffle(rng);
     }
 
     pub fn pop(&mut self) -> Option<Card> {
@@ -4,7 +4,7 @@ use lazy_static::lazy_static;
 
 use crate::poker::card::Card;
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Play {
     Highest(Vec<usize>),
     Pair(usize, Vec<usize>),
@@ -46,58 +46,56 @@ impl Play {
 
     pub fn name(&self) -> String {
         match self {
-            Play::Highest(k) => format!("High card {}", value_to_str(k[4])),
+            Play::Highest(k) => format!("High card {}", value_to_str(k[k.len() - 1])),
             Play::Pair(p, ..) => format!("Pair of {}s", value_to_str(*p)),
             Play::DoublePair(p1, p2, ..) => format!("Two Pairs: {}s and {}s", value_to_str(*p1), value_to_str(*p2)),
             Play::ThreeOfAKind(t, ..) => format!("Three {}s", value_to_str(*t)),
             Play::Straight(s) => format!("Straight to {}", value_to_str(*s)),
-            Play::Flush(..) => format!("Flush"),
+            Play::Flush(..) => "Flush".into(),
             Play::FullHouse(t, p) => format!("{}s full of {}s", value_to_str(*t), value_to_str(*p)),
             Play::FourOfAKind(f, ..) => format!("Four {}s", value_to_str(*f)),
             Play::StraightFlush(s, ..) => format!("Straight Flush to {}", value_to_str(*s)),
-            Play::RoyalFlush => format!("Royal Flush"),
+            Play::RoyalFlush => "Royal Flush".into(),
         }
     }
 }
 
 impl PartialOrd for Play {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Play {
+    fn cmp(&self, other: &Self) -> Ordering {
         use Play::*;
 
         match self.priority().cmp(&other.priority()) {
             Ordering::Equal => {
                 match (self, other) {
-                    (Highest(k1), Highest(k2)) => Some(compare_kickers(k1, k2)),
-                    (Pair(p1, k1), Pair(p2, k2)) => Some(p1.cmp(p2).then_with(|| compare_kickers(k1, k2))),
-                    (DoublePair(p11, p12, k1), DoublePair(p21, p22, k2)) => Some(
-                        p11.cmp(p21)
+                    (Highest(k1), Highest(k2)) => compare_kickers(k1, k2),
+                    (Pair(p1, k1), Pair(p2, k2)) => p1.cmp(p2).then_with(|| compare_kickers(k1, k2)),
+                    (DoublePair(p11, p12, k1), DoublePair(p21, p22, k2)) => p11.cmp(p21)
                         .then_with(|| p12.cmp(p22))
-                        .then_with(|| compare_kickers(k1, k2))
-                    ),
-                    (ThreeOfAKind(t1, k1), ThreeOfAKind(t2, k2)) => Some(t1.cmp(t2).then_with(|| compare_kickers(k1, k2))),
-                    (Straight(h1), Straight(h2)) => Some(h1.cmp(h2)),
-                    (Flush(k1), Flush(k2)) => Some(compare_kickers(k1, k2)),
-                    (FullHouse(t1, p1), FullHouse(t2, p2)) => Some(t1.cmp(t2).then_with(|| p1.cmp(p2))),
-                    (FourOfAKind(f1, k1), FourOfAKind(f2, k2)) => Some(f1.cmp(f2).then_with(|| compare_kickers(k1, k2))),
-                    (StraightFlush(h1), StraightFlush(h2)) => Some(h1.cmp(h2)),
-                    (RoyalFlush, RoyalFlush) => Some(Ordering::Equal),
+                        .then_with(|| compare_kickers(k1, k2)),
+                    (ThreeOfAKind(t1, k1), ThreeOfAKind(t2, k2)) => t1.cmp(t2).then_with(|| compare_kickers(k1, k2)),
+                    (Straight(h1), Straight(h2)) => h1.cmp(h2),
+                    (Flush(k1), Flush(k2)) => compare_kickers(k1, k2),
+                    (FullHouse(t1, p1), FullHouse(t2, p2)) => t1.cmp(t2).then_with(|| p1.cmp(p2)),
+                    (FourOfAKind(f1, k1), FourOfAKind(f2, k2)) => f1.cmp(f2).then_with(|| compare_kickers(k1, k2)),
+                    (StraightFlush(h1), StraightFlush(h2)) => h1.cmp(h2),
+                    (RoyalFlush, RoyalFlush) => Ordering::Equal,
 
                     _ => unreachable!()
                 }    
             },
 
-            c => Some(c)
+            c => c
         }
     }
 }
 
-impl Ord for Play {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
-pub fn compare_kickers(a: &Vec<usize>, b: &Vec<usize>) -> Ordering {
+pub fn compare_kickers(a: &[usize], b: &[usize]) -> Ordering {
     for (i, j) in a.iter().zip(b).rev() {
         match i.cmp(j) {
             Ordering::Equal => { },
@@ -137,11 +135,11 @@ pub fn analyze_play(hand: &[Card], community: &[Card]) -> Play {
     let mut straight_found = None;
 
     for ns in STRAIGHTS.iter() {
-        let has_all = ns.iter().all(|i| numbers.contains_key(&i));
+        let has_all = ns.iter().all(|i| numbers.contains_key(i));
 
         if has_all {
             let suits = ns.iter()
-                .map(|i| numbers.get(&i).unwrap())
+                .map(|i| numbers.get(i).unwrap())
                 .map(|s| s.iter().map(|c| c.suit.clone()).collect::<HashSet<_>>())
                 .reduce(|a, b| a.intersection(&b).cloned().collect())
                 .unwrap();
@@ -183,8 +181,7 @@ pub fn analyze_play(hand: &[Card], community: &[Card]) -> Play {
         suits.entry(c.suit.clone()).or_default().push(*c);
     }
 
-    let flush = suits.iter()
-        .map(|(_, i)| i)
+    let flush = suits.values()
         .find(|&cs| cs.len() >= 5)
         .cloned()
         .map(|mut cs| {
@@ -267,6 +264,6 @@ pub fn analyze_play(hand: &[Card], community: &[Card]) -> Play {
         return Play::Pair(pairs[0], kickers);
     }
 
-    // Return highest card
-    Play::Highest(all[all.len() - 5..].iter().map(|c| c.value()).collect())
+    // Return highest card, hands that end before the river have fewer than five
+    Play::Highest(all[all.len().saturating_sub(5)..].iter().map(|c| c.value()).collect())
 }
\ No newline at end of file